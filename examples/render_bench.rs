@@ -0,0 +1,54 @@
+//! Time drawing a list of 100k items, building a row for every item the way
+//! the list used to, against building only the rows on screen.
+//!
+//! ```sh
+//! cargo run --release --example render_bench
+//! ```
+
+use std::time::{Duration, Instant};
+use tui::{backend::TestBackend, widgets::{Block, Borders, List, ListItem, ListState}, Terminal};
+use tui_frontend::{render, App, Item};
+
+const ITEMS: usize = 100_000;
+const FRAMES: u32 = 50;
+const WIDTH: u16 = 120;
+const HEIGHT: u16 = 40;
+
+// Average time per frame of `draw`, run once beforehand to warm up
+fn time(mut draw: impl FnMut()) -> Duration {
+    draw();
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        draw();
+    }
+    start.elapsed() / FRAMES
+}
+
+fn main() {
+    let labels: Vec<String> = (0..ITEMS).map(|i| format!("item {i}")).collect();
+
+    // Before: a ListItem for every label, leaving tui's List to pick out
+    // the ones that fit
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).expect("the test backend can't fail");
+    let mut state = ListState::default();
+    state.select(Some(ITEMS / 2));
+    let full = time(|| {
+        terminal.draw(|f| {
+            let items: Vec<ListItem> = labels.iter().map(|label| ListItem::new(label.as_str())).collect();
+            let list = List::new(items).block(Block::default().borders(Borders::ALL)).highlight_symbol(">> ");
+            f.render_stateful_widget(list, f.size(), &mut state);
+        }).expect("the test backend can't fail");
+    });
+
+    // After: the picker itself, which only builds the rows on screen
+    let items = labels.iter().enumerate().map(|(i, label)| Item::new(label.as_str(), i)).collect();
+    let mut app = App::builder().items(items).build();
+    app.list_mut().select_index(ITEMS / 2);
+    let windowed = time(|| {
+        render(&mut app, WIDTH, HEIGHT);
+    });
+
+    println!("{ITEMS} items, {FRAMES} frames of {WIDTH}x{HEIGHT}");
+    println!("every row built: {full:>10.2?} a frame");
+    println!("visible rows:    {windowed:>10.2?} a frame");
+}
@@ -0,0 +1,101 @@
+//! Callbacks run when the user activates an item with Enter.
+
+use std::process::{Command, Stdio};
+
+use crate::model::Item;
+
+/// What the app should do after an item was activated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Activation {
+    /// Exit, returning the activated item as the selection
+    Quit,
+    /// Keep the app open
+    Stay,
+    /// Keep the app open and show the message as an error
+    Error(String),
+}
+
+pub type OnActivate = Box<dyn FnMut(&Item) -> Activation>;
+
+/// Whether activating an item can end the picker.
+///
+/// In `Stay` mode an activation that would quit instead adds the item to the
+/// picked items and leaves the app open. Quitting then returns everything
+/// picked so far, so the binary prints those items on exit rather than
+/// nothing.
+///
+/// In `SelectAndContinue` mode every activation that doesn't fail adds the
+/// item to the picks and marks it visited, whatever the callback asks for,
+/// to build up a queue one item at a time. The done key, Ctrl-Enter or `D`,
+/// then returns the picks in the order they were made:
+///
+/// ```
+/// use crossterm::event::KeyCode;
+/// use tui::{backend::TestBackend, Terminal};
+/// use tui_frontend::{activate::{self, ActivateMode}, run_app, App, AppEvent, Config, Item, ScriptedSource};
+///
+/// let mut app = App::builder()
+///     .config(Config { activate_mode: ActivateMode::SelectAndContinue, ..Config::default() })
+///     .items(vec![Item::new("one", 1), Item::new("two", 2), Item::new("three", 3)])
+///     .on_activate(activate::run_command("true"))
+///     .build();
+/// let keys = [KeyCode::Char('j'), KeyCode::Char('j'), KeyCode::Enter, KeyCode::Char('k'), KeyCode::Enter, KeyCode::Char('D')];
+/// let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+/// let picked = run_app(&mut terminal, &mut app, &mut ScriptedSource::new(keys.map(AppEvent::key))).unwrap();
+/// let labels: Vec<_> = picked.iter().map(|selection| selection.label.as_str()).collect();
+/// assert_eq!(labels, ["three", "two"]);
+/// assert!(app.list().items[1].visited);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActivateMode {
+    SelectAndQuit,
+    Stay,
+    SelectAndContinue,
+}
+
+/// Quit with the activated item as the selection.
+///
+/// Callbacks run while the app owns the terminal, so nothing is printed
+/// here; the binary prints the selection returned by `run` once the
+/// terminal is restored.
+pub fn print_and_quit() -> OnActivate {
+    Box::new(|_| Activation::Quit)
+}
+
+/// The command line `run_command` runs for `label`.
+///
+/// ```
+/// use tui_frontend::activate::resolve_command;
+///
+/// assert_eq!(resolve_command("rm {}", "my file"), "rm 'my file'");
+/// ```
+pub fn resolve_command(command: &str, label: &str) -> String {
+    command.replace("{}", &shell_quote(label))
+}
+
+/// Run `command` through `sh -c` and stay open. Every `{}` in the command is
+/// replaced by the item's label, quoted for the shell.
+pub fn run_command(command: impl Into<String>) -> OnActivate {
+    let command = command.into();
+    Box::new(move |item| {
+        let line = resolve_command(&command, &item.label);
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(&line)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        match status {
+            Ok(status) if status.success() => Activation::Stay,
+            Ok(status) => Activation::Error(format!("{}: {}", line, status)),
+            Err(err) => Activation::Error(format!("{}: {}", line, err)),
+        }
+    })
+}
+
+/// Quote `s` as a single word for a POSIX shell, wrapping it in single
+/// quotes and closing and reopening them around embedded quotes.
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
@@ -0,0 +1,1630 @@
+use std::{collections::{HashMap, HashSet}, fs, path::Path, time::{Duration, Instant}};
+use tui::{layout::Rect, style::Style};
+use crossterm::event::KeyEvent;
+use tui_textarea::{CursorMove, TextArea};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    activate::{ActivateMode, OnActivate}, bidi, builder::AppBuilder, clipboard::{self, OnCopy}, complete::{self, Completion}, config::{AppendSelection, Config, Overflow}, filter::{self, PayloadFilter},
+    history::History, key_handler::KeyHandler, keymap::{Action, Keymap, LEADER_TIMEOUT}, list::StatefulList, model::{Item, Level, Tab, TabState}, preview::Preview,
+    state::State, status::{self, Place, Segment}, tab_switch::OnTabSwitch, theme::Theme, ui, Selection,
+};
+
+// How long a status message stays in the status bar
+const STATUS_TIMEOUT: Duration = Duration::from_secs(3);
+// Number of edits that can be undone
+const UNDO_LIMIT: usize = 100;
+// Fewest columns dragging the divider can leave either pane
+const MIN_PANE_WIDTH: u16 = 10;
+// Percentage of the width the list starts with
+const DEFAULT_SPLIT: u16 = 50;
+
+/// A region of the screen that can receive mouse input. The list and the
+/// details can also have keyboard focus.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pane {
+    Tabs,
+    List,
+    Detail,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Moving through the list. Esc here backs out of one thing at a time,
+    /// where the other modes go back to this one
+    ///
+    /// ```
+    /// use crossterm::event::KeyCode;
+    /// use tui::{backend::TestBackend, Terminal};
+    /// use tui_frontend::{run_app, App, AppEvent, Config, Item, Mode, ScriptedSource};
+    ///
+    /// let mut app = App::builder()
+    ///     .config(Config { confirm_destructive: true, initial_selection: false, ..Config::default() })
+    ///     .items(vec![Item::new("apple", 1), Item::new("pear", 2)])
+    ///     .build();
+    /// let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+    /// let mut press = |app: &mut App, keys: &[KeyCode]| {
+    ///     let events = keys.iter().map(|&key| AppEvent::key(key));
+    ///     run_app(&mut terminal, app, &mut ScriptedSource::new(events))
+    /// };
+    ///
+    /// // A question in a popup is answered no
+    /// let _ = press(&mut app, &[KeyCode::Char('j'), KeyCode::Char('d')]);
+    /// assert!(app.mode == Mode::Confirm);
+    /// let _ = press(&mut app, &[KeyCode::Esc]);
+    /// assert!(app.mode == Mode::Normal);
+    /// assert_eq!(app.list().items.len(), 2);
+    ///
+    /// // The search and command prompts are left, the search's query with it
+    /// let _ = press(&mut app, &[KeyCode::Char('/'), KeyCode::Char('p'), KeyCode::Esc]);
+    /// assert!(app.mode == Mode::Normal && app.query.is_empty());
+    /// let _ = press(&mut app, &[KeyCode::Char(':'), KeyCode::Esc]);
+    /// assert!(app.mode == Mode::Normal);
+    ///
+    /// // A committed search is cleared, then the selection, then it quits
+    /// let _ = press(&mut app, &[KeyCode::Char('/'), KeyCode::Char('r'), KeyCode::Enter]);
+    /// assert_eq!(app.list().view, [1]);
+    /// let _ = press(&mut app, &[KeyCode::Esc]);
+    /// assert_eq!(app.list().view, [0, 1]);
+    /// assert!(app.list().state.selected().is_some());
+    /// let _ = press(&mut app, &[KeyCode::Esc]);
+    /// assert_eq!(app.list().state.selected(), None);
+    /// assert_eq!(press(&mut app, &[KeyCode::Esc]).unwrap(), []);
+    /// ```
+    Normal,
+    Command,
+    /// Typing a search query, which filters the list with every key
+    ///
+    /// ```
+    /// use crossterm::event::KeyCode;
+    /// use tui::{backend::TestBackend, Terminal};
+    /// use tui_frontend::{run_app, App, AppEvent, Item, ScriptedSource};
+    ///
+    /// let mut app = App::builder()
+    ///     .items(vec![Item::new("apple", 1), Item::new("pear", 2), Item::new("plum", 3)])
+    ///     .build();
+    /// let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+    /// let steps: [(KeyCode, &[usize]); 6] = [
+    ///     (KeyCode::Char('/'), &[0, 1, 2]),
+    ///     (KeyCode::Char('p'), &[0, 1, 2]),
+    ///     (KeyCode::Char('l'), &[0, 2]),
+    ///     (KeyCode::Char('u'), &[2]),
+    ///     (KeyCode::Backspace, &[0, 2]),
+    ///     (KeyCode::Backspace, &[0, 1, 2]),
+    /// ];
+    /// for (key, view) in steps {
+    ///     // Each run stops once its one key has been handled
+    ///     let _ = run_app(&mut terminal, &mut app, &mut ScriptedSource::new([AppEvent::key(key)]));
+    ///     assert_eq!(app.list().view, view, "after {:?}", key);
+    /// }
+    /// ```
+    Search,
+    /// Extending a range of items to check from the anchor
+    Visual,
+    /// Waiting for y or n to a question in a popup
+    Confirm,
+    /// Waiting for the letters of a hint to jump to the row it labels
+    Hint,
+}
+
+/// Figures about the active list, shown in the stats footer. Payload
+/// figures cover the items the filter leaves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Items in the list, not counting separators
+    pub total: usize,
+    /// Items left by the filter
+    pub shown: usize,
+    pub sum: usize,
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Stats {
+    pub fn average(&self) -> f64 {
+        if self.shown == 0 { 0.0 } else { self.sum as f64 / self.shown as f64 }
+    }
+}
+
+/// Order the active list is shown in, cycled through with a key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortState {
+    /// The order the items were loaded in
+    #[default]
+    Original,
+    LabelAsc,
+    LabelDesc,
+    PayloadAsc,
+    PayloadDesc,
+}
+
+impl SortState {
+    /// The order after this one, going through the labels then the
+    /// payloads and back to the original order.
+    ///
+    /// ```
+    /// use tui_frontend::SortState;
+    ///
+    /// let mut sort = SortState::Original;
+    /// let cycle: Vec<SortState> = (0..5).map(|_| { sort = sort.next(); sort }).collect();
+    /// assert_eq!(cycle, [
+    ///     SortState::LabelAsc,
+    ///     SortState::LabelDesc,
+    ///     SortState::PayloadAsc,
+    ///     SortState::PayloadDesc,
+    ///     SortState::Original,
+    /// ]);
+    /// ```
+    pub fn next(self) -> SortState {
+        match self {
+            SortState::Original => SortState::LabelAsc,
+            SortState::LabelAsc => SortState::LabelDesc,
+            SortState::LabelDesc => SortState::PayloadAsc,
+            SortState::PayloadAsc => SortState::PayloadDesc,
+            SortState::PayloadDesc => SortState::Original,
+        }
+    }
+
+    /// How the order is described in the status bar.
+    pub fn describe(self) -> &'static str {
+        match self {
+            SortState::Original => "original order",
+            SortState::LabelAsc => "sorted by label ↑",
+            SortState::LabelDesc => "sorted by label ↓",
+            SortState::PayloadAsc => "sorted by payload ↑",
+            SortState::PayloadDesc => "sorted by payload ↓",
+        }
+    }
+}
+
+// A destructive action waiting for the user to confirm it
+pub(crate) struct PendingConfirm {
+    pub message: String,
+    pub action: Box<dyn FnOnce(&mut App)>,
+}
+
+// A tab's items as they were before an edit
+pub(crate) struct UndoEntry {
+    tab: usize,
+    items: Vec<Item>,
+    checked: HashSet<usize>,
+    selected: Option<usize>,
+}
+
+pub(crate) struct StatusMessage {
+    pub text: String,
+    pub error: bool,
+    pub shown_at: Instant,
+}
+
+/// State of a running picker.
+pub struct App {
+    /// Always holds at least one tab; the selected tab is the active one
+    pub tabs: StatefulList<Tab>,
+    pub config: Config,
+    /// The theme in use, unless the active tab has its own
+    pub theme: Theme,
+    /// Themes to cycle through by name, the current one at `theme_index`
+    pub themes: Vec<(String, Theme)>,
+    pub theme_index: usize,
+    pub keymap: Keymap,
+    /// How often `on_tick` runs, expiring status messages and the like
+    pub tick_rate: Duration,
+    /// Longest the event loop waits for input before drawing again
+    pub poll_timeout: Duration,
+    pub mode: Mode,
+    /// Text the active tab's list is narrowed down by
+    pub query: String,
+    /// Order the active tab's list is shown in
+    pub sort_state: SortState,
+    /// Labels of items kept at the top of the list, in the order they were
+    /// pinned
+    pub pinned: Vec<String>,
+    /// Set by `:filter`, narrows the list down by payload as well
+    pub(crate) payload_filter: Option<PayloadFilter>,
+    // Tab whose view the query, sort and payload filter are, which other
+    // tabs keep in their `TabState`
+    state_tab: usize,
+    /// Called on Enter; without it Enter quits with the selection
+    pub on_activate: Option<OnActivate>,
+    /// Items activated while staying open, returned when the user quits
+    pub picked: Vec<Selection>,
+    /// Called whenever another tab becomes active
+    pub on_tab_switch: Option<OnTabSwitch>,
+    /// Given the text of the copy actions
+    pub on_copy: OnCopy,
+    /// Tried in order before the keymap in normal mode
+    pub key_handlers: Vec<KeyHandler>,
+    /// Drawn on the status bar in order, starting with the mode
+    pub status_segments: Vec<(Place, Segment)>,
+    /// Shown in the detail pane instead of the item's details
+    pub preview: Option<Preview>,
+    /// Set while a background command is running, holding back actions
+    /// that would race with it
+    pub busy: bool,
+    pub(crate) input: TextArea<'static>,
+    // Completions of the command prompt, while Tab is cycling through them
+    pub(crate) completion: Option<Completion>,
+    pub search_history: History,
+    pub command_history: History,
+    /// When the leader key was pressed, while waiting for the rest of a sequence
+    pub(crate) pending_leader: Option<Instant>,
+    pub(crate) status: Option<StatusMessage>,
+    pub(crate) pending_confirm: Option<PendingConfirm>,
+    // Letters of a hint typed so far
+    pub(crate) hint_input: String,
+    pub(crate) undo: Vec<UndoEntry>,
+    /// Item last yanked or deleted, put back by paste
+    pub register: Option<Item>,
+    /// Keys recorded into each macro register
+    pub macros: HashMap<char, Vec<KeyEvent>>,
+    // The register being recorded into and the keys so far
+    pub(crate) recording: Option<(char, Vec<KeyEvent>)>,
+    // Record or Replay, while waiting for the key naming the register
+    pub(crate) pending_register: Option<Action>,
+    // How many macros are being replayed inside one another
+    pub(crate) macro_depth: usize,
+    // Set when a reload was asked for, so "reloading…" is drawn before the
+    // event loop blocks on the source
+    pub(crate) reload_requested: bool,
+    // Set when the screen should be cleared before the next draw, to get rid
+    // of anything left on it by other programs
+    pub(crate) clear_requested: bool,
+    // Inner area of the tabs bar and the columns each tab title covers,
+    // recorded by ui() so mouse clicks can be mapped back to tabs
+    pub(crate) tabs_area: Rect,
+    pub(crate) tab_bounds: Vec<(u16, u16)>,
+    pub(crate) list_area: Rect,
+    pub(crate) detail_area: Rect,
+    // Area split between the list and the details, and the column of the
+    // details' left edge, where the divider is
+    pub(crate) panes_area: Rect,
+    pub(crate) divider_x: u16,
+    /// Percentage of the width given to the list, the rest going to the
+    /// details
+    pub split: u16,
+    // Set while the divider is being dragged
+    pub(crate) dragging_divider: bool,
+    /// Index into the active list of the item showing its details inline
+    pub(crate) expanded: Option<usize>,
+    /// Lines the detail pane is scrolled down by
+    pub(crate) detail_scroll: u16,
+    /// Pane that movement keys act on
+    pub focus: Pane,
+    /// Show only the list, without borders, tabs or the status bar
+    pub zen: bool,
+    /// Keep the newest item selected as items stream in
+    pub following: bool,
+    // Rows of the list drawn in the last frame as (top, bottom, view position)
+    pub(crate) row_bounds: Vec<(u16, u16, usize)>,
+    // Columns and rows the list's labels had in the last frame, which decide
+    // how tall a wrapped item is
+    pub(crate) list_text_width: usize,
+    pub(crate) list_height: usize,
+    // Columns the list was drawn in, and where the second starts as its
+    // screen column and first view position
+    pub(crate) list_columns: usize,
+    pub(crate) second_column: Option<(u16, usize)>,
+    // Lines of the selected row as drawn in the last frame, without padding
+    // at their ends
+    pub(crate) selected_row: Vec<String>,
+    /// Index into the active list of the last clicked item
+    pub(crate) last_click: Option<usize>,
+    /// Smallest and largest payload in the active list
+    pub(crate) payload_range: (usize, usize),
+    stats: Stats,
+}
+
+impl App {
+    /// An app filled with demo data.
+    pub fn new(config: Config) -> App {
+        let items = || vec![
+            Item::new("Item0", 1),
+            Item::new("Item1", 2),
+            Item::new("Item2", 3),
+        ];
+        App::builder()
+            .config(config)
+            .tab("Test0", items())
+            .tab("Test1", items())
+            .tab("Test2", items())
+            .tab("Test3", items())
+            .build()
+    }
+
+    pub fn builder() -> AppBuilder {
+        AppBuilder::default()
+    }
+
+    pub(crate) fn from_parts(config: Config, theme: Theme, keymap: Keymap, tick_rate: Duration, mut tabs: Vec<Tab>) -> App {
+        if tabs.is_empty() {
+            tabs.push(Tab::new("", Vec::new()));
+        }
+        for tab in &mut tabs {
+            tab.cap(config.max_items);
+        }
+        let following = config.follow;
+        let mut tabs = StatefulList::with_items(tabs);
+        tabs.next();
+        let mut app = App {
+            tabs,
+            config,
+            theme,
+            themes: Vec::new(),
+            theme_index: 0,
+            keymap,
+            tick_rate,
+            poll_timeout: Duration::from_millis(16),
+            mode: Mode::Normal,
+            query: String::new(),
+            sort_state: SortState::Original,
+            pinned: Vec::new(),
+            payload_filter: None,
+            state_tab: 0,
+            on_activate: None,
+            picked: Vec::new(),
+            on_tab_switch: None,
+            on_copy: clipboard::terminal(),
+            key_handlers: Vec::new(),
+            status_segments: vec![(Place::Left, status::mode())],
+            preview: None,
+            busy: false,
+            input: TextArea::default(),
+            completion: None,
+            search_history: History::default(),
+            command_history: History::default(),
+            pending_leader: None,
+            status: None,
+            pending_confirm: None,
+            hint_input: String::new(),
+            undo: Vec::new(),
+            register: None,
+            macros: HashMap::new(),
+            recording: None,
+            pending_register: None,
+            macro_depth: 0,
+            reload_requested: false,
+            clear_requested: false,
+            tabs_area: Rect::default(),
+            tab_bounds: Vec::new(),
+            list_area: Rect::default(),
+            detail_area: Rect::default(),
+            panes_area: Rect::default(),
+            divider_x: 0,
+            split: DEFAULT_SPLIT,
+            dragging_divider: false,
+            expanded: None,
+            detail_scroll: 0,
+            focus: Pane::List,
+            zen: false,
+            following,
+            row_bounds: Vec::new(),
+            list_text_width: 0,
+            list_height: 0,
+            list_columns: 1,
+            second_column: None,
+            selected_row: Vec::new(),
+            last_click: None,
+            payload_range: (0, 0),
+            stats: Stats::default(),
+        };
+        app.apply_filter();
+        app
+    }
+
+    pub fn on_tick(&mut self) {
+        if let Some(status) = &self.status {
+            if status.shown_at.elapsed() >= STATUS_TIMEOUT {
+                self.status = None;
+            }
+        }
+        if self.pending_leader.is_some_and(|t| t.elapsed() >= LEADER_TIMEOUT) {
+            self.pending_leader = None;
+        }
+    }
+
+    pub fn set_status(&mut self, text: impl Into<String>) {
+        self.status = Some(StatusMessage { text: text.into(), error: false, shown_at: Instant::now() });
+    }
+
+    pub fn set_error(&mut self, text: impl Into<String>) {
+        self.status = Some(StatusMessage { text: text.into(), error: true, shown_at: Instant::now() });
+    }
+
+    pub fn active_tab(&self) -> &Tab {
+        self.tabs.selected_item().unwrap_or(&self.tabs.items[0])
+    }
+
+    /// The active tab's own theme if it has one, else the app's.
+    pub fn active_theme(&self) -> &Theme {
+        self.active_tab().theme.as_ref().unwrap_or(&self.theme)
+    }
+
+    /// The list shown in the active tab.
+    pub fn list(&self) -> &StatefulList<Item> {
+        &self.active_tab().items
+    }
+
+    pub fn list_mut(&mut self) -> &mut StatefulList<Item> {
+        let i = self.tabs.selected_index().unwrap_or(0);
+        &mut self.tabs.items[i].items
+    }
+
+    /// The currently highlighted item, if any.
+    pub fn selection(&self) -> Option<Selection> {
+        self.list().selected_index().map(|i| self.selection_at(i))
+    }
+
+    /// The checked items in list order, or the highlighted item if none
+    /// are checked.
+    pub fn selections(&self) -> Vec<Selection> {
+        // Separators are never picked, even if one was selected
+        let list = self.list();
+        let mut checked: Vec<usize> = list.checked.iter().copied().collect();
+        if checked.is_empty() {
+            return self.selection().into_iter().filter(|s| !list.items[s.index].separator).collect();
+        }
+        checked.sort_unstable();
+        checked.into_iter().map(|i| self.selection_at(i)).collect()
+    }
+
+    fn selection_at(&self, index: usize) -> Selection {
+        let item = &self.list().items[index];
+        Selection {
+            index,
+            label: item.label.clone(),
+            payload: item.payload,
+            tab: self.active_tab().title.clone(),
+        }
+    }
+
+    /// Label the rows on screen with hints to jump to by typing them.
+    ///
+    /// ```
+    /// use tui::{backend::TestBackend, Terminal};
+    /// use tui_frontend::{run_app, App, Item, ScriptedSource};
+    ///
+    /// let mut app = App::builder()
+    ///     .items(["apple", "pear", "plum"].iter().enumerate().map(|(i, label)| Item::new(*label, i)).collect())
+    ///     .build();
+    /// let mut terminal = Terminal::new(TestBackend::new(40, 16)).unwrap();
+    /// run_app(&mut terminal, &mut app, &mut ScriptedSource::keys("gcq")).unwrap();
+    /// assert_eq!(app.list().selected_item().unwrap().label, "plum");
+    /// ```
+    pub fn start_hints(&mut self) {
+        self.hint_input.clear();
+        self.mode = Mode::Hint;
+    }
+
+    /// The rows among `bounds` that can be selected, each with the letters
+    /// of its hint: one letter for up to 26 rows, then two. Rows of a frame
+    /// drawn before the view shrank, such as by a replayed filter, are
+    /// left out.
+    pub(crate) fn hint_targets(&self, bounds: &[(u16, u16, usize)]) -> Vec<(String, usize)> {
+        let list = self.list();
+        let positions: Vec<usize> = bounds.iter()
+            .map(|&(_, _, position)| position)
+            .filter(|&position| list.view.get(position).is_some_and(|&index| !list.items[index].separator))
+            .collect();
+        let labels: Vec<String> = if positions.len() <= 26 {
+            ('a'..='z').map(String::from).collect()
+        } else {
+            ('a'..='z').flat_map(|a| ('a'..='z').map(move |b| format!("{}{}", a, b))).collect()
+        };
+        labels.into_iter().zip(positions).collect()
+    }
+
+    pub(crate) fn start_visual(&mut self) {
+        if let Some(index) = self.list().selected_index() {
+            self.list_mut().anchor = Some(index);
+            self.mode = Mode::Visual;
+        }
+    }
+
+    pub(crate) fn end_visual(&mut self, confirm: bool) {
+        let list = self.list_mut();
+        if confirm {
+            list.check_range();
+        }
+        list.anchor = None;
+        self.mode = Mode::Normal;
+    }
+
+    /// Switch to the next tab, wrapping around to the first.
+    ///
+    /// ```
+    /// use tui_frontend::{App, Item};
+    ///
+    /// let mut app = App::builder()
+    ///     .tab("one", vec![Item::new("a", 1)])
+    ///     .tab("two", Vec::new())
+    ///     .tab("three", vec![Item::separator()])
+    ///     .tab("four", vec![Item::new("b", 2)])
+    ///     .build();
+    /// app.next_tab();
+    /// assert_eq!(app.active_tab().title, "two");
+    ///
+    /// // Tabs with nothing to select are passed over, both ways
+    /// app.config.skip_empty_tabs = true;
+    /// app.next_tab();
+    /// assert_eq!(app.active_tab().title, "four");
+    /// app.next_tab();
+    /// assert_eq!(app.active_tab().title, "one");
+    /// app.previous_tab();
+    /// assert_eq!(app.active_tab().title, "four");
+    /// ```
+    pub fn next_tab(&mut self) {
+        self.step_tab(StatefulList::next);
+    }
+
+    pub fn previous_tab(&mut self) {
+        self.step_tab(StatefulList::previous);
+    }
+
+    // Move between tabs with `step`, going on past empty tabs when they are
+    // skipped. If every other tab is empty the active tab stays.
+    fn step_tab(&mut self, step: fn(&mut StatefulList<Tab>)) {
+        let start = self.tabs.state.selected();
+        step(&mut self.tabs);
+        if self.config.skip_empty_tabs {
+            for _ in 1..self.tabs.items.len() {
+                if !self.active_tab().is_empty() {
+                    break;
+                }
+                step(&mut self.tabs);
+            }
+            if self.active_tab().is_empty() {
+                self.tabs.state.select(start);
+            }
+        }
+        self.tab_switched();
+    }
+
+    pub fn select_tab(&mut self, index: usize) {
+        if index < self.tabs.items.len() {
+            self.tabs.state.select(Some(index));
+            self.tab_switched();
+        }
+    }
+
+    /// Let `on_tab_switch` see the newly active tab, then re-filter it.
+    /// Items it fills in are capped like those loaded up front, and the
+    /// first of them is selected.
+    pub(crate) fn tab_switched(&mut self) {
+        self.swap_tab_state();
+        let index = self.tabs.selected_index().unwrap_or(0);
+        let switched = match &mut self.on_tab_switch {
+            Some(on_tab_switch) => {
+                on_tab_switch(index, &mut self.tabs.items[index]);
+                true
+            }
+            None => false,
+        };
+        // Capping again when nothing is over the limit would forget how many
+        // were dropped when the tab was loaded
+        let tab = &mut self.tabs.items[index];
+        if let Some(max) = self.config.max_items.filter(|&max| tab.items.items.len() > max) {
+            tab.cap(Some(max));
+        }
+        self.apply_filter();
+        if switched && self.config.initial_selection && self.list().state.selected().is_none() {
+            self.list_mut().select_nearest(0);
+        }
+    }
+
+    /// Show the selected item's children in place of the active tab's list,
+    /// with a filter of their own. Returns false if the item has none.
+    ///
+    /// ```
+    /// use tui_frontend::{App, Item};
+    ///
+    /// let fruit = Item::new("fruit", 1).with_children(vec![Item::new("apple", 1), Item::new("pear", 2)]);
+    /// let mut app = App::builder().items(vec![fruit, Item::new("bread", 2)]).build();
+    /// app.list_mut().next();
+    /// assert!(app.open_children());
+    /// assert_eq!(app.list().selected_item().unwrap().label, "apple");
+    /// assert!(!app.open_children());
+    ///
+    /// app.list_mut().remove_selected();
+    /// assert!(app.close_level());
+    /// assert_eq!(app.list().selected_item().unwrap().children.len(), 1);
+    /// assert!(!app.close_level());
+    /// ```
+    pub fn open_children(&mut self) -> bool {
+        let item = match self.list().selected_item() {
+            Some(item) if !item.children.is_empty() => item,
+            _ => return false,
+        };
+        let label = item.label.clone();
+        let children = StatefulList::with_items(item.children.clone());
+        let state = TabState {
+            query: std::mem::take(&mut self.query),
+            sort_state: std::mem::take(&mut self.sort_state),
+            payload_filter: self.payload_filter.take(),
+        };
+        let index = self.tabs.selected_index().unwrap_or(0);
+        let tab = &mut self.tabs.items[index];
+        let items = std::mem::replace(&mut tab.items, children);
+        tab.levels.push(Level { label, items, state });
+        self.expanded = None;
+        self.apply_filter();
+        self.list_mut().next();
+        true
+    }
+
+    /// Go back up to the list the shown one was opened from, keeping any
+    /// edits made to the children. Returns false at the top level.
+    pub fn close_level(&mut self) -> bool {
+        let index = self.tabs.selected_index().unwrap_or(0);
+        let tab = &mut self.tabs.items[index];
+        let level = match tab.levels.pop() {
+            Some(level) => level,
+            None => return false,
+        };
+        let children = std::mem::replace(&mut tab.items, level.items).items;
+        if let Some(parent) = tab.items.selected_index() {
+            tab.items.items[parent].children = children;
+        }
+        self.query = level.state.query;
+        self.sort_state = level.state.sort_state;
+        self.payload_filter = level.state.payload_filter;
+        self.expanded = None;
+        self.apply_filter();
+        true
+    }
+
+    // Put the view of the tab that was active back on it and take out the
+    // active tab's
+    fn swap_tab_state(&mut self) {
+        let index = self.tabs.selected_index().unwrap_or(0);
+        if index == self.state_tab {
+            return;
+        }
+        let state = TabState {
+            query: std::mem::take(&mut self.query),
+            sort_state: self.sort_state,
+            payload_filter: self.payload_filter.take(),
+        };
+        self.tabs.items[self.state_tab].state = state;
+        let state = std::mem::take(&mut self.tabs.items[index].state);
+        self.query = state.query;
+        self.sort_state = state.sort_state;
+        self.payload_filter = state.payload_filter;
+        self.state_tab = index;
+    }
+
+    /// Move items found in `mru` to the top of every tab, most recent
+    /// first, keeping the rest in their original order.
+    pub fn order_by_mru(&mut self, mru: &[String]) {
+        let rank = |item: &Item| mru.iter().position(|l| *l == item.label).unwrap_or(usize::MAX);
+        for tab in &mut self.tabs.items {
+            tab.items.items.sort_by_key(rank);
+        }
+        self.apply_filter();
+    }
+
+    /// Apply data saved by earlier runs.
+    pub(crate) fn restore(&mut self, state: &State) {
+        if self.config.mru {
+            self.order_by_mru(&state.mru);
+        }
+        if self.config.track_visited {
+            for tab in &mut self.tabs.items {
+                for item in &mut tab.items.items {
+                    item.visited = state.visited.contains(&item.label);
+                }
+            }
+        }
+        if self.config.save_history {
+            self.search_history = History::new(state.searches.clone());
+            self.command_history = History::new(state.commands.clone());
+        }
+        if self.config.save_pins {
+            self.pinned = state.pins.clone();
+            self.apply_filter();
+        }
+        // A theme asked for on the command line wins over the saved one
+        if let (true, None, Some(name)) = (self.config.save_theme, &self.config.theme, &state.theme) {
+            self.select_theme(name);
+        }
+    }
+
+    /// Record this run's selections and visits for the next run.
+    pub(crate) fn persist(&self, state: &mut State, selections: &[Selection]) {
+        if self.config.mru {
+            for selection in selections.iter().rev() {
+                state.record(&selection.label);
+            }
+        }
+        if self.config.track_visited {
+            let items = || self.tabs.items.iter().flat_map(|t| t.items.items.iter());
+            // Labels not loaded this run keep whatever state they had
+            state.visited.retain(|label| !items().any(|i| i.label == *label));
+            for item in items().filter(|i| i.visited) {
+                if !state.visited.contains(&item.label) {
+                    state.visited.push(item.label.clone());
+                }
+            }
+        }
+        if self.config.save_history {
+            state.searches = self.search_history.entries.clone();
+            state.commands = self.command_history.entries.clone();
+        }
+        if self.config.save_pins {
+            state.pins = self.pinned.clone();
+        }
+        if self.config.save_theme {
+            state.theme = self.themes.get(self.theme_index).map(|(name, _)| name.clone());
+        }
+    }
+
+    /// Switch to the theme called `name`. Returns whether there is one.
+    pub fn select_theme(&mut self, name: &str) -> bool {
+        match self.themes.iter().position(|(n, _)| n == name) {
+            Some(index) => {
+                self.theme_index = index;
+                self.theme = self.themes[index].1.clone();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Switch to the next theme, going back to the first after the last,
+    /// and say which it is.
+    ///
+    /// ```
+    /// use tui_frontend::{App, Config};
+    ///
+    /// let mut app = App::new(Config::default());
+    /// let names: Vec<String> = app.themes.iter().map(|(name, _)| name.clone()).collect();
+    /// assert_eq!(names, ["default", "light", "mono"]);
+    /// app.cycle_theme();
+    /// assert_eq!(app.theme_index, 1);
+    /// app.cycle_theme();
+    /// app.cycle_theme();
+    /// assert_eq!(app.theme_index, 0);
+    /// ```
+    pub fn cycle_theme(&mut self) {
+        if self.themes.is_empty() {
+            return;
+        }
+        let index = (self.theme_index + 1) % self.themes.len();
+        let name = self.themes[index].0.clone();
+        self.select_theme(&name);
+        self.set_status(format!("theme: {}", name));
+    }
+
+    /// Stop whatever is running in the background, returning to idle.
+    /// Returns false if nothing was.
+    pub fn cancel(&mut self) -> bool {
+        if !self.preview.as_mut().is_some_and(Preview::cancel) {
+            return false;
+        }
+        self.busy = false;
+        self.set_status("cancelled");
+        true
+    }
+
+    pub(crate) fn update_preview(&mut self) {
+        let label = self.list().selected_item().map(|i| i.label.clone());
+        if let Some(preview) = &mut self.preview {
+            preview.update(label.as_deref());
+            self.busy = preview.is_running();
+        }
+    }
+
+    /// Append lines streamed in since the last frame to their tabs, as
+    /// items numbered on from the last. Once a tab holds `max_items`, later
+    /// lines are dropped like those beyond it when loading.
+    pub(crate) fn receive_streamed(&mut self) {
+        let active = self.tabs.selected_index().unwrap_or(0);
+        let max_items = self.config.max_items;
+        let mut arrived = false;
+        for (index, tab) in self.tabs.items.iter_mut().enumerate() {
+            let lines = match &tab.stream {
+                Some(stream) => stream.drain(),
+                None => continue,
+            };
+            for line in lines {
+                if max_items.is_some_and(|max| tab.items.items.len() >= max) {
+                    tab.dropped += 1;
+                    continue;
+                }
+                let payload = tab.items.items.len() + 1;
+                tab.items.items.push(Item::new(line, payload));
+                arrived |= index == active;
+            }
+        }
+        // Other tabs' views are brought up to date when they're switched to
+        if !arrived {
+            return;
+        }
+        let position = self.list().state.selected();
+        self.apply_filter();
+        if self.following || self.config.on_append == AppendSelection::Newest {
+            self.select_newest();
+        } else if position.is_none() {
+            if self.config.initial_selection {
+                self.list_mut().next();
+            }
+        } else if self.config.on_append == AppendSelection::SameIndex {
+            let len = self.list().view.len();
+            self.list_mut().state.select(position.map(|p| p.min(len.saturating_sub(1))));
+        }
+    }
+
+    /// Select the newest item and keep the newest selected from now on.
+    pub fn follow(&mut self) {
+        self.following = true;
+        self.select_newest();
+    }
+
+    // Items are only ever appended, so the newest has the highest index
+    fn newest_position(&self) -> Option<usize> {
+        let view = &self.list().view;
+        view.iter().enumerate().max_by_key(|&(_, &index)| index).map(|(position, _)| position)
+    }
+
+    fn select_newest(&mut self) {
+        let newest = self.newest_position();
+        self.list_mut().state.select(newest);
+    }
+
+    // Following stops once the user moves off the newest item
+    pub(crate) fn pause_follow_if_moved(&mut self) {
+        let selected = self.list().state.selected();
+        if selected.is_some() && selected != self.newest_position() {
+            self.following = false;
+        }
+    }
+
+    pub fn mark_selected_visited(&mut self) {
+        if let Some(index) = self.list().selected_index() {
+            self.list_mut().items[index].visited = true;
+        }
+    }
+
+    /// Show the selected item's details beneath it in the list, or hide
+    /// them again.
+    pub fn toggle_expanded(&mut self) {
+        let selected = self.list().selected_index();
+        self.expanded = if self.expanded == selected { None } else { selected };
+    }
+
+    /// Hide the inline details once the selection has moved off their item.
+    pub fn collapse_if_moved(&mut self) {
+        if self.expanded.is_some() && self.expanded != self.list().selected_index() {
+            self.expanded = None;
+        }
+    }
+
+    /// Extra lines shown beneath the item at `index` while it is expanded.
+    pub(crate) fn expansion(&self, index: usize) -> Vec<String> {
+        if self.expanded != Some(index) {
+            return Vec::new();
+        }
+        let item = &self.list().items[index];
+        let mut lines = vec![format!("payload: {}", item.payload)];
+        if let Some(meta) = &item.meta {
+            lines.push(meta.clone());
+        }
+        lines.push(format!("tab: {}", self.active_tab().title));
+        lines
+    }
+
+    /// Pin the selected item to the top of the list, or unpin it.
+    ///
+    /// ```
+    /// use tui_frontend::{App, Item};
+    ///
+    /// let mut app = App::builder()
+    ///     .tab("Fruit", vec![Item::new("apple", 3), Item::new("banana", 1), Item::new("cherry", 2)])
+    ///     .build();
+    /// app.list_mut().select_index(2);
+    /// app.toggle_pin();
+    /// // Pinned items stay first whichever way the rest are sorted
+    /// for _ in 0..5 {
+    ///     app.cycle_sort();
+    ///     assert_eq!(app.list().visible().next().unwrap().label, "cherry");
+    /// }
+    /// ```
+    pub fn toggle_pin(&mut self) {
+        let label = match self.list().selected_item() {
+            Some(item) => item.label.clone(),
+            None => return,
+        };
+        if let Some(i) = self.pinned.iter().position(|l| *l == label) {
+            self.pinned.remove(i);
+            self.set_status(format!("unpinned {}", label));
+        } else {
+            self.set_status(format!("pinned {}", label));
+            self.pinned.push(label);
+        }
+        self.apply_filter();
+    }
+
+    /// Where an item with `label` goes among the pinned ones, or after all of
+    /// them if it isn't pinned.
+    pub(crate) fn pin_rank(&self, label: &str) -> usize {
+        self.pinned.iter().position(|l| l == label).unwrap_or(usize::MAX)
+    }
+
+    /// Switch to the next sort order, keeping the same item selected.
+    pub fn cycle_sort(&mut self) {
+        self.sort_state = self.sort_state.next();
+        self.apply_filter();
+        self.set_status(self.sort_state.describe());
+    }
+
+    /// Cycle between quitting on Enter, staying open to pick more and
+    /// picking every activated item.
+    pub fn toggle_activate_mode(&mut self) {
+        self.config.activate_mode = match self.config.activate_mode {
+            ActivateMode::SelectAndQuit => ActivateMode::Stay,
+            ActivateMode::Stay => ActivateMode::SelectAndContinue,
+            ActivateMode::SelectAndContinue => ActivateMode::SelectAndQuit,
+        };
+        self.set_status(match self.config.activate_mode {
+            ActivateMode::SelectAndQuit => "Enter picks and quits",
+            ActivateMode::Stay => "Enter picks and stays",
+            ActivateMode::SelectAndContinue => "Enter runs and adds to the picks, D finishes",
+        });
+    }
+
+    // Keep the selections Enter would have quit with, in Stay mode
+    pub(crate) fn pick(&mut self) {
+        let selections = self.selections();
+        let count = selections.len();
+        self.picked.extend(selections);
+        self.set_status(format!("picked {} ({} so far)", count, self.picked.len()));
+    }
+
+    /// Switch between showing only the list and the full layout. Only the
+    /// list is left to focus.
+    pub fn toggle_zen(&mut self) {
+        self.zen = !self.zen;
+        self.focus = Pane::List;
+    }
+
+    pub fn toggle_wrap(&mut self) {
+        self.config.overflow = match self.config.overflow {
+            Overflow::Truncate => Overflow::Wrap,
+            Overflow::Wrap => Overflow::Truncate,
+        };
+        self.set_status(match self.config.overflow {
+            Overflow::Truncate => "truncating long labels",
+            Overflow::Wrap => "wrapping long labels",
+        });
+    }
+
+    pub fn toggle_all_read(&mut self) {
+        let visited = !self.list().items.iter().all(|i| i.visited);
+        for item in &mut self.list_mut().items {
+            item.visited = visited;
+        }
+    }
+
+    pub(crate) fn request_reload(&mut self) {
+        if self.active_tab().source.is_none() {
+            return self.set_error("nothing to reload");
+        }
+        self.set_status("reloading…");
+        self.reload_requested = true;
+    }
+
+    /// Load the active tab's items from their source again, keeping the
+    /// selection on the item with the same label.
+    ///
+    /// ```
+    /// use std::fs;
+    /// use tui_frontend::{App, Source};
+    ///
+    /// let path = std::env::temp_dir().join(format!("tui-frontend-reload-{}.txt", std::process::id()));
+    /// fs::write(&path, "a\nb\nc\n").unwrap();
+    /// let source = Source::File(path.clone());
+    /// let mut app = App::builder().source_tab("letters", source.load().unwrap(), source).build();
+    /// let selected = |app: &App| (app.list().state.selected(), app.list().selected_item().unwrap().label.clone());
+    ///
+    /// // Items added before the selection push it down with its item
+    /// app.list_mut().select_index(1);
+    /// fs::write(&path, "x\ny\na\nb\nc\n").unwrap();
+    /// app.reload();
+    /// assert_eq!(selected(&app), (Some(3), "b".to_string()));
+    ///
+    /// // With the selected item gone, the selection stays at its position
+    /// fs::write(&path, "x\ny\na\nc\n").unwrap();
+    /// app.reload();
+    /// assert_eq!(selected(&app), (Some(3), "c".to_string()));
+    ///
+    /// // Or the last position, if the list got shorter than that
+    /// fs::write(&path, "x\ny\n").unwrap();
+    /// app.reload();
+    /// assert_eq!(selected(&app), (Some(1), "y".to_string()));
+    /// fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn reload(&mut self) {
+        self.reload_requested = false;
+        let source = match &self.active_tab().source {
+            Some(source) => source.clone(),
+            None => return,
+        };
+        let (items, invalid) = match source.load_lossy() {
+            Ok(loaded) => loaded,
+            Err(err) => return self.set_error(format!("reload: {}", err)),
+        };
+        // The source only has the top level
+        while self.close_level() {}
+        let label = self.list().selected_item().map(|i| i.label.clone());
+        let position = self.list().state.selected();
+        let count = items.len();
+        let max_items = self.config.max_items;
+        let i = self.tabs.selected_index().unwrap_or(0);
+        let tab = &mut self.tabs.items[i];
+        tab.items.items = items;
+        tab.items.checked.clear();
+        tab.items.unselect();
+        tab.cap(max_items);
+        self.apply_filter();
+        let list = self.list_mut();
+        list.state.select(position);
+        match label {
+            Some(label) => list.reselect_by_label(&label),
+            None => list.next(),
+        }
+        if invalid > 0 {
+            self.set_status(format!("reloaded {} items, {}", count, invalid_utf8_message(invalid)));
+        } else {
+            self.set_status(format!("reloaded {} items", count));
+        }
+    }
+
+    /// Remember the active tab's items so the next edit can be undone.
+    pub fn checkpoint(&mut self) {
+        self.expanded = None;
+        let entry = UndoEntry {
+            tab: self.tabs.selected_index().unwrap_or(0),
+            items: self.list().items.clone(),
+            checked: self.list().checked.clone(),
+            selected: self.list().selected_index(),
+        };
+        if self.undo.len() == UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(entry);
+    }
+
+    /// Revert the most recent edit, switching to the tab it was made in.
+    pub fn undo(&mut self) {
+        let entry = match self.undo.pop() {
+            Some(entry) => entry,
+            None => return self.set_status("nothing to undo"),
+        };
+        self.tabs.state.select(Some(entry.tab));
+        self.swap_tab_state();
+        let list = self.list_mut();
+        list.items = entry.items;
+        list.checked = entry.checked;
+        list.unselect();
+        self.apply_filter();
+        if let Some(index) = entry.selected {
+            self.list_mut().select_index(index);
+        }
+    }
+
+    pub fn duplicate_selected(&mut self) {
+        if self.list().selected_index().is_none() {
+            return;
+        }
+        self.checkpoint();
+        self.list_mut().duplicate_selected();
+        self.update_stats();
+    }
+
+    /// Copy the selected item into the register.
+    pub fn yank_selected(&mut self) {
+        if let Some(item) = self.list().selected_item() {
+            self.register = Some(item.clone());
+        }
+    }
+
+    /// Copy the selected item's label, or with `displayed` its row as drawn.
+    pub fn copy_selected(&mut self, displayed: bool) {
+        let text = match self.list().selected_item() {
+            Some(_) if displayed => self.selected_row.join("\n"),
+            Some(item) => item.label.clone(),
+            None => return,
+        };
+        match (self.on_copy)(&text) {
+            Ok(()) => self.set_status("copied"),
+            Err(err) => self.set_error(format!("copy: {}", err)),
+        }
+    }
+
+    /// Remove the selected item, keeping it in the register.
+    pub fn delete_selected(&mut self) {
+        if self.list().selected_index().is_none() {
+            return;
+        }
+        self.checkpoint();
+        self.register = self.list_mut().remove_selected();
+        self.update_stats();
+    }
+
+    /// Insert the register's item after the selection.
+    ///
+    /// ```
+    /// use tui_frontend::{App, Item};
+    ///
+    /// let mut app = App::builder().items(vec![Item::new("a", 1), Item::new("b", 2), Item::new("c", 3)]).build();
+    /// let labels = |app: &App| app.list().visible().map(|i| i.label.clone()).collect::<Vec<_>>();
+    /// let selected = |app: &App| app.list().selected_item().unwrap().label.clone();
+    ///
+    /// // Yanking leaves the item in place, and the copy is selected
+    /// app.list_mut().select_index(0);
+    /// app.yank_selected();
+    /// app.list_mut().select_index(2);
+    /// app.paste();
+    /// assert_eq!(labels(&app), ["a", "b", "c", "a"]);
+    /// assert_eq!(selected(&app), "a");
+    /// assert_eq!(app.list().state.selected(), Some(3));
+    ///
+    /// // Deleting selects the next item, and pasting puts it back after that
+    /// app.list_mut().select_index(1);
+    /// app.delete_selected();
+    /// assert_eq!(labels(&app), ["a", "c", "a"]);
+    /// assert_eq!(selected(&app), "c");
+    /// app.paste();
+    /// assert_eq!(labels(&app), ["a", "c", "b", "a"]);
+    /// assert_eq!(app.list().state.selected(), Some(2));
+    ///
+    /// // Each edit is undone in turn, restoring the selection it was made on
+    /// app.undo();
+    /// assert_eq!(labels(&app), ["a", "c", "a"]);
+    /// assert_eq!(selected(&app), "c");
+    /// app.undo();
+    /// assert_eq!(labels(&app), ["a", "b", "c", "a"]);
+    /// assert_eq!(selected(&app), "b");
+    /// app.undo();
+    /// assert_eq!(labels(&app), ["a", "b", "c"]);
+    /// assert_eq!(selected(&app), "c");
+    /// ```
+    pub fn paste(&mut self) {
+        let item = match &self.register {
+            Some(item) => item.clone(),
+            None => return self.set_status("nothing to paste"),
+        };
+        self.checkpoint();
+        self.list_mut().insert_after_selected(item);
+        self.update_stats();
+    }
+
+    // Items in the order they are currently shown
+    pub(crate) fn visible_items(&self) -> impl Iterator<Item = &Item> {
+        self.list().visible()
+    }
+
+    /// Recompute which items match the current query. Called whenever the
+    /// active list changes.
+    ///
+    /// Every word of the query has to appear in a label, except words
+    /// starting with `!`, which must not. `\!` starts a word looking for a
+    /// literal `!`.
+    ///
+    /// ```
+    /// use tui_frontend::{App, Item};
+    ///
+    /// let labels = ["red apple", "green apple", "red pear", "!important", "wow!"];
+    /// let mut app = App::builder()
+    ///     .items(labels.iter().enumerate().map(|(i, label)| Item::new(*label, i)).collect())
+    ///     .build();
+    /// let mut matching = |query: &str| {
+    ///     app.query = query.to_string();
+    ///     app.apply_filter();
+    ///     app.list().visible().map(|i| i.label.clone()).collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(matching("apple !green"), ["red apple"]);
+    /// assert_eq!(matching("red !pear !apple"), Vec::<String>::new());
+    /// assert_eq!(matching("!red !apple"), ["!important", "wow!"]);
+    /// assert_eq!(matching("\\!imp"), ["!important"]);
+    /// assert_eq!(matching("\\! !wow"), ["!important"]);
+    /// // A lone `!` excludes nothing
+    /// assert_eq!(matching("pear !"), ["red pear"]);
+    /// ```
+    pub fn apply_filter(&mut self) {
+        self.expanded = None;
+        let payloads = self.list().items.iter().filter(|i| !i.separator).map(|i| i.payload);
+        self.payload_range = (payloads.clone().min().unwrap_or(0), payloads.max().unwrap_or(0));
+        let sorted = self.sort_state != SortState::Original;
+        let ignore_case = self.config.case.ignores_case(&self.query);
+        let mut view: Vec<usize> = self.list().items.iter()
+            .enumerate()
+            .filter(|(_, item)| if self.config.search_all_fields {
+                filter::matches(&item.search_text(), &self.query, ignore_case)
+            } else {
+                filter::matches(&item.label, &self.query, ignore_case)
+            })
+            .filter(|(_, item)| self.payload_filter.as_ref().is_none_or(|f| f(item.payload)))
+            // Separators divide the whole list, so they go while it's
+            // filtered or sorted
+            .filter(|(_, item)| !item.separator || (self.query.is_empty() && self.payload_filter.is_none() && !sorted))
+            .map(|(i, _)| i)
+            .collect();
+        let items = &self.list().items;
+        match self.sort_state {
+            SortState::Original => {}
+            SortState::LabelAsc => view.sort_by(|&a, &b| items[a].label.cmp(&items[b].label)),
+            SortState::LabelDesc => view.sort_by(|&a, &b| items[b].label.cmp(&items[a].label)),
+            SortState::PayloadAsc => view.sort_by_key(|&i| items[i].payload),
+            SortState::PayloadDesc => view.sort_by(|&a, &b| items[b].payload.cmp(&items[a].payload)),
+        }
+        if !self.pinned.is_empty() {
+            view.sort_by_key(|&i| self.pin_rank(&items[i].label));
+        }
+        self.list_mut().set_view(view);
+        self.update_stats();
+    }
+
+    /// Figures about the active list as of its last change.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    // Kept up to date as the list or the filter change rather than worked
+    // out every frame
+    fn update_stats(&mut self) {
+        let list = self.list();
+        let shown = || list.visible().filter(|i| !i.separator).map(|i| i.payload);
+        self.stats = Stats {
+            total: list.items.iter().filter(|i| !i.separator).count(),
+            shown: shown().count(),
+            sum: shown().sum(),
+            min: shown().min().unwrap_or(0),
+            max: shown().max().unwrap_or(0),
+        };
+    }
+
+    pub(crate) fn enter_command(&mut self, prefill: &str) {
+        self.completion = None;
+        self.command_history.reset();
+        self.reset_input(prefill);
+        self.mode = Mode::Command;
+    }
+
+    pub(crate) fn enter_search(&mut self) {
+        let query = self.query.clone();
+        self.search_history.reset();
+        self.reset_input(&query);
+        self.mode = Mode::Search;
+    }
+
+    // Update the query from the search prompt, re-filtering only if it changed
+    pub(crate) fn sync_query(&mut self) {
+        if self.input.lines()[0] != self.query {
+            self.query = self.input.lines()[0].clone();
+            self.apply_filter();
+        }
+    }
+
+    pub(crate) fn reset_input(&mut self, text: &str) {
+        self.input = TextArea::default();
+        self.input.set_cursor_line_style(Style::default());
+        self.input.insert_str(text);
+        self.input.move_cursor(CursorMove::End);
+    }
+
+    /// Complete the command prompt's input, or switch to the next
+    /// completion if Tab was the last key pressed.
+    pub(crate) fn complete_command(&mut self) {
+        if self.completion.is_none() {
+            let candidates = complete::complete(&self.input.lines()[0]);
+            match candidates.len() {
+                0 => return,
+                // An only completion is taken along with the space after it
+                1 => return self.reset_input(&format!("{} ", candidates[0])),
+                _ => self.completion = Some(Completion::new(candidates)),
+            }
+        }
+        if let Some(completion) = &mut self.completion {
+            let line = completion.advance().to_string();
+            self.reset_input(&line);
+        }
+    }
+
+    pub(crate) fn run_command(&mut self, line: &str) {
+        let line = line.trim();
+        let (name, arg) = match line.split_once(char::is_whitespace) {
+            Some((name, arg)) => (name, arg.trim()),
+            None => (line, ""),
+        };
+        match name {
+            "" => {}
+            "w" | "write" => self.write_view(arg),
+            "filter" => self.filter_payload(arg),
+            "reset" => self.reset_view(),
+            "tab" => self.goto_tab(arg),
+            "sort" => self.sort_by(arg),
+            _ => self.set_error(format!("unknown command: {}", name)),
+        }
+    }
+
+    /// Run each line of the file at `path` as a `:` command, in order.
+    /// Blank lines and ones starting with `#` are skipped, and a leading `:`
+    /// is allowed. Commands that fail are reported and the rest still run.
+    pub fn run_init(&mut self, path: &Path) {
+        let script = match fs::read_to_string(path) {
+            Ok(script) => script,
+            Err(err) => return self.set_error(format!("init: {}: {}", path.display(), err)),
+        };
+        let mut failures = Vec::new();
+        for (n, line) in script.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            self.status = None;
+            self.run_command(line.strip_prefix(':').unwrap_or(line));
+            if let Some(status) = self.status.take().filter(|s| s.error) {
+                failures.push(format!("{}:{}: {}", path.display(), n + 1, status.text));
+            }
+        }
+        for failure in &failures {
+            eprintln!("warning: {}", failure);
+        }
+        match failures.as_slice() {
+            [] => {}
+            [failure] => self.set_error(failure.clone()),
+            [failure, rest @ ..] => self.set_error(format!("{} (and {} more)", failure, rest.len())),
+        }
+    }
+
+    // Switch to the tab numbered `arg` from 1, or else titled `arg`
+    fn goto_tab(&mut self, arg: &str) {
+        let index = match arg.parse::<usize>() {
+            Ok(n) => n.checked_sub(1),
+            Err(_) => self.tabs.items.iter().position(|t| t.title == arg),
+        };
+        match index {
+            Some(i) if i < self.tabs.items.len() => self.select_tab(i),
+            _ => self.set_error(format!("tab: no tab {}", arg)),
+        }
+    }
+
+    // Sort by `label` or `payload`, optionally followed by `desc`, or go back
+    // to the original order with `none`
+    fn sort_by(&mut self, arg: &str) {
+        let words: Vec<&str> = arg.split_whitespace().collect();
+        self.sort_state = match words.as_slice() {
+            ["none"] => SortState::Original,
+            ["label"] | ["label", "asc"] => SortState::LabelAsc,
+            ["label", "desc"] => SortState::LabelDesc,
+            ["payload"] | ["payload", "asc"] => SortState::PayloadAsc,
+            ["payload", "desc"] => SortState::PayloadDesc,
+            _ => return self.set_error(format!("sort: expected label, payload or none, got {}", arg)),
+        };
+        self.apply_filter();
+        self.set_status(self.sort_state.describe());
+    }
+
+    /// Go back to the view the app started with: no search, payload filter
+    /// or sorting, the panes split evenly, focus on the list and the first
+    /// item selected. Items and which are checked are left alone.
+    pub fn reset_view(&mut self) {
+        self.mode = Mode::Normal;
+        self.split = DEFAULT_SPLIT;
+        self.query.clear();
+        self.payload_filter = None;
+        self.sort_state = SortState::Original;
+        self.focus = Pane::List;
+        self.detail_scroll = 0;
+        self.apply_filter();
+        let list = self.list_mut();
+        list.anchor = None;
+        list.offset = 0;
+        list.select_nearest(0);
+    }
+
+    // Narrow the list down to payloads matching `predicate`, or show them all
+    // again when it is empty
+    fn filter_payload(&mut self, predicate: &str) {
+        if predicate.is_empty() {
+            self.payload_filter = None;
+        } else {
+            match filter::parse_payload_filter(predicate) {
+                Ok(f) => self.payload_filter = Some(f),
+                Err(err) => return self.set_error(format!("filter: {}", err)),
+            }
+        }
+        self.apply_filter();
+        self.set_status(format!("{} items shown", self.list().view.len()));
+    }
+
+    /// Whether the given screen position is on the border between the list
+    /// and the details, taking either side's edge.
+    pub(crate) fn on_divider(&self, x: u16, y: u16) -> bool {
+        let area = self.panes_area;
+        y >= area.top() && y < area.bottom() && (x == self.divider_x || x + 1 == self.divider_x)
+    }
+
+    /// Move the divider to column `x`, leaving each pane at least a few
+    /// columns.
+    pub(crate) fn drag_divider(&mut self, x: u16) {
+        let area = self.panes_area;
+        if area.width == 0 {
+            return;
+        }
+        let min = MIN_PANE_WIDTH.min(area.width / 2);
+        let left = x.saturating_sub(area.x).clamp(min, area.width - min);
+        self.split = (left as u32 * 100 / area.width as u32) as u16;
+    }
+
+    /// The pane drawn at the given screen position in the last frame.
+    pub fn pane_at(&self, x: u16, y: u16) -> Option<Pane> {
+        let contains = |area: Rect| x >= area.left() && x < area.right() && y >= area.top() && y < area.bottom();
+        if self.config.show_tabs && contains(self.tabs_area) {
+            Some(Pane::Tabs)
+        } else if contains(self.list_area) {
+            Some(Pane::List)
+        } else if contains(self.detail_area) {
+            Some(Pane::Detail)
+        } else {
+            None
+        }
+    }
+
+    /// Metadata shown at the end of `item`'s row, if any.
+    pub(crate) fn meta(&self, item: &Item) -> Option<String> {
+        match &item.meta {
+            Some(meta) => Some(meta.clone()),
+            None if self.config.payload_column => Some(item.payload.to_string()),
+            None => None,
+        }
+    }
+
+    // Columns left for a label once its icon, metadata and badge, each with
+    // a space next to it, are set aside. The badge is padded by a space on
+    // each side. Right-to-left labels also leave room for the selection
+    // marker after them.
+    pub(crate) fn label_room(&self, item: &Item) -> usize {
+        let icon = ui::icon_width(item);
+        let meta = self.meta(item).map_or(0, |meta| meta.width() + 1);
+        let badge = item.badge.as_ref().map_or(0, |(badge, _)| badge.width() + 3);
+        let marker = if self.is_rtl(item) { ui::RTL_HIGHLIGHT_SYMBOL.len() } else { 0 };
+        self.list_text_width.saturating_sub(icon + meta + badge + marker)
+    }
+
+    /// Whether `item`'s row is laid out right to left.
+    pub(crate) fn is_rtl(&self, item: &Item) -> bool {
+        self.config.rtl && bidi::is_rtl(&item.label)
+    }
+
+    /// Rows the item at `position` in the view takes up in the list. An item
+    /// taller than the list is cut short to fit.
+    pub(crate) fn row_height(&self, position: usize) -> usize {
+        if self.list_columns > 1 {
+            return 1;
+        }
+        let index = self.list().view[position];
+        let lines = match self.config.overflow {
+            Overflow::Truncate => 1,
+            Overflow::Wrap => {
+                let item = &self.list().items[index];
+                ui::wrapped_line_count(&item.label, self.label_room(item))
+            }
+        };
+        let subtitle = self.list().items[index].subtitle.is_some() as usize;
+        (lines + subtitle + self.expansion(index).len() + self.config.row_spacing as usize).min(self.list_height.max(1))
+    }
+
+    /// Scroll the list down a screen, selecting the first item that wasn't
+    /// fully shown.
+    pub fn page_down(&mut self) {
+        let len = self.list().view.len();
+        let top = match self.row_bounds.first() {
+            Some(&(top, _, _)) => top as usize,
+            None => return,
+        };
+        let bottom = top + self.list_height;
+        let next = self.row_bounds.iter()
+            .find(|&&(_, b, _)| b as usize > bottom)
+            .map_or_else(|| self.row_bounds.last().map_or(0, |&(_, _, p)| p + 1), |&(_, _, p)| p)
+            .min(len.saturating_sub(1));
+        let list = self.list_mut();
+        list.offset = next;
+        list.select_nearest(next);
+    }
+
+    /// Scroll the list up a screen, selecting the top item.
+    pub fn page_up(&mut self) {
+        let mut offset = self.list().offset;
+        let mut used = 0;
+        while offset > 0 {
+            let height = self.row_height(offset - 1);
+            if used + height > self.list_height {
+                break;
+            }
+            used += height;
+            offset -= 1;
+        }
+        let list = self.list_mut();
+        list.offset = offset;
+        list.select_nearest(offset);
+    }
+
+    /// Scroll the list by half its height without moving the selection,
+    /// unless it would leave the screen, in which case it stays on the
+    /// nearest row still shown. PageDown and PageUp move the selection
+    /// instead.
+    pub fn scroll_half_page(&mut self, down: bool) {
+        let len = self.list().view.len();
+        if len == 0 {
+            return;
+        }
+        let half = (self.list_height / 2).max(1);
+        let mut offset = self.list().offset;
+        let mut moved = 0;
+        while moved < half {
+            if down && offset + 1 < len {
+                moved += self.row_height(offset);
+                offset += 1;
+            } else if !down && offset > 0 {
+                offset -= 1;
+                moved += self.row_height(offset);
+            } else {
+                break;
+            }
+        }
+        // Last item that fits entirely below the new offset
+        let mut last = offset;
+        let mut used = self.row_height(offset);
+        while last + 1 < len && used + self.row_height(last + 1) <= self.list_height {
+            last += 1;
+            used += self.row_height(last);
+        }
+        let list = self.list_mut();
+        list.offset = offset;
+        let selected = list.state.selected().unwrap_or(offset);
+        list.select_nearest(selected.clamp(offset, last));
+    }
+
+    /// View position of the list row drawn at screen cell (`x`, `y`).
+    pub(crate) fn row_at(&self, x: u16, y: u16) -> Option<usize> {
+        let in_second = |position: usize| self.second_column.is_some_and(|(_, first)| position >= first);
+        let clicked_second = self.second_column.is_some_and(|(start, _)| x >= start);
+        self.row_bounds.iter()
+            .find(|&&(top, bottom, position)| y >= top && y < bottom && in_second(position) == clicked_second)
+            .map(|&(_, _, position)| position)
+    }
+
+    pub(crate) fn tab_at(&self, x: u16, y: u16) -> Option<usize> {
+        let area = self.tabs_area;
+        if y < area.top() || y >= area.bottom() {
+            return None;
+        }
+        self.tab_bounds.iter().position(|&(start, end)| x >= start && x < end)
+    }
+
+    fn write_view(&mut self, path: &str) {
+        if path.is_empty() {
+            return self.set_error("write: no file name");
+        }
+        if Path::new(path).is_dir() {
+            return self.set_error(format!("write: {} is a directory", path));
+        }
+        let items = || self.visible_items().filter(|i| !i.separator);
+        let contents: String = items().map(|i| format!("{}\n", i.label)).collect();
+        let count = items().count();
+        match fs::write(path, contents) {
+            Ok(()) => self.set_status(format!("wrote {} items to {}", count, path)),
+            Err(err) => self.set_error(format!("write: {}: {}", path, err)),
+        }
+    }
+}
+
+// Status message about lines of a source that weren't valid UTF-8
+pub(crate) fn invalid_utf8_message(invalid: usize) -> String {
+    match invalid {
+        1 => "1 line had invalid UTF-8".to_string(),
+        n => format!("{} lines had invalid UTF-8", n),
+    }
+}
@@ -0,0 +1,24 @@
+//! Telling right-to-left text apart. Terminals differ in whether they
+//! reorder bidirectional text themselves, so labels are only aligned here,
+//! never reordered, and their widths come from `unicode-width` like any
+//! other text's, with combining marks taking no room.
+
+/// Whether most of the letters in `text` are written right to left, as in
+/// Arabic or Hebrew. Digits, punctuation and spaces don't count either way.
+pub(crate) fn is_rtl(text: &str) -> bool {
+    let (mut rtl, mut ltr) = (0, 0);
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        if is_rtl_char(c) {
+            rtl += 1;
+        } else {
+            ltr += 1;
+        }
+    }
+    rtl > ltr
+}
+
+// Hebrew, Arabic, Syriac, Thaana, N'Ko and their neighbours, their
+// presentation forms, and the historic right-to-left scripts
+fn is_rtl_char(c: char) -> bool {
+    matches!(c as u32, 0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF)
+}
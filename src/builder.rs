@@ -0,0 +1,224 @@
+use std::time::Duration;
+
+use crate::{activate::{self, OnActivate}, app::App, clipboard::OnCopy, config::Config, key_handler::KeyHandler, keymap::Keymap, model::{Item, Tab}, preview::Preview, source::Source, spec::Spec, status::{Place, Segment}, stream::Stream, tab_switch::OnTabSwitch, theme::Theme};
+
+/// Builds an [`App`] from your own data.
+///
+/// ```
+/// use tui_frontend::{App, Item};
+///
+/// let app = App::builder()
+///     .tab("Fruit", vec![Item::new("apple", 1), Item::new("pear", 2)])
+///     .tab("Veg", vec![Item::new("leek", 3)])
+///     .build();
+/// assert_eq!(app.tabs.items.len(), 2);
+/// ```
+pub struct AppBuilder {
+    config: Config,
+    theme: Theme,
+    themes: Vec<(String, Theme)>,
+    keymap: Keymap,
+    tick_rate: Duration,
+    poll_timeout: Duration,
+    tabs: Vec<Tab>,
+    on_activate: Option<OnActivate>,
+    on_tab_switch: Option<OnTabSwitch>,
+    on_copy: Option<OnCopy>,
+    key_handlers: Vec<KeyHandler>,
+    status_segments: Vec<(Place, Segment)>,
+}
+
+impl Default for AppBuilder {
+    fn default() -> AppBuilder {
+        AppBuilder {
+            config: Config::default(),
+            theme: Theme::default(),
+            themes: Vec::new(),
+            keymap: Keymap::default(),
+            tick_rate: Duration::from_millis(250),
+            poll_timeout: Duration::from_millis(16),
+            tabs: Vec::new(),
+            on_activate: None,
+            on_tab_switch: None,
+            on_copy: None,
+            key_handlers: Vec::new(),
+            status_segments: Vec::new(),
+        }
+    }
+}
+
+impl AppBuilder {
+    pub fn config(mut self, config: Config) -> AppBuilder {
+        self.config = config;
+        self
+    }
+
+    pub fn theme(mut self, theme: Theme) -> AppBuilder {
+        self.theme = theme;
+        self
+    }
+
+    pub fn keymap(mut self, keymap: Keymap) -> AppBuilder {
+        self.keymap = keymap;
+        self
+    }
+
+    pub fn tick_rate(mut self, tick_rate: Duration) -> AppBuilder {
+        self.tick_rate = tick_rate;
+        self
+    }
+
+    /// Longest to wait for input before drawing again. Keeps keys responsive
+    /// however long the tick rate is.
+    pub fn poll_timeout(mut self, poll_timeout: Duration) -> AppBuilder {
+        self.poll_timeout = poll_timeout;
+        self
+    }
+
+    /// Add a tab holding `items`. Tabs are shown in the order they are added.
+    pub fn tab(mut self, title: impl Into<String>, items: Vec<Item>) -> AppBuilder {
+        self.tabs.push(Tab::new(title, items));
+        self
+    }
+
+    /// Add a tab holding `items` that were loaded from `source`, so they can
+    /// be reloaded while the app runs.
+    pub fn source_tab(mut self, title: impl Into<String>, items: Vec<Item>, source: Source) -> AppBuilder {
+        let mut tab = Tab::new(title, items);
+        tab.source = Some(source);
+        self.tabs.push(tab);
+        self
+    }
+
+    /// Add a tab that starts empty and gets a new item for each line from
+    /// `stream` while the app runs.
+    pub fn stream_tab(mut self, title: impl Into<String>, stream: Stream) -> AppBuilder {
+        let mut tab = Tab::new(title, Vec::new());
+        tab.stream = Some(stream);
+        self.tabs.push(tab);
+        self
+    }
+
+    /// Add a theme that can be switched to by `name` while the app runs. The
+    /// app starts with the first one added. Without any, the app's theme
+    /// is cycled with the built-in light and monochrome themes.
+    pub fn named_theme(mut self, name: impl Into<String>, theme: Theme) -> AppBuilder {
+        self.themes.push((name.into(), theme));
+        self
+    }
+
+    /// Give the most recently added tab its own theme, used while it is
+    /// active.
+    pub fn tab_theme(mut self, theme: Theme) -> AppBuilder {
+        if let Some(tab) = self.tabs.last_mut() {
+            tab.theme = Some(theme);
+        }
+        self
+    }
+
+    /// Add a tab for each tab the spec describes.
+    pub fn spec(mut self, spec: &Spec) -> AppBuilder {
+        for tab in &spec.tabs {
+            self = self.tab(tab.title.as_str(), tab.items());
+        }
+        self
+    }
+
+    /// Add a single tab holding `items`.
+    pub fn items(self, items: Vec<Item>) -> AppBuilder {
+        self.tab("List", items)
+    }
+
+    /// Call `on_activate` with the selected item when Enter is pressed,
+    /// instead of quitting straight away.
+    pub fn on_activate(mut self, on_activate: OnActivate) -> AppBuilder {
+        self.on_activate = Some(on_activate);
+        self
+    }
+
+    /// Call `on_tab_switch` with each tab as it becomes active, starting
+    /// with the first one when the app is built.
+    pub fn on_tab_switch(mut self, on_tab_switch: OnTabSwitch) -> AppBuilder {
+        self.on_tab_switch = Some(on_tab_switch);
+        self
+    }
+
+    /// Hand the text of the copy actions to `on_copy` rather than the
+    /// terminal's clipboard.
+    pub fn on_copy(mut self, on_copy: OnCopy) -> AppBuilder {
+        self.on_copy = Some(on_copy);
+        self
+    }
+
+    /// Try `handler` on keys in normal mode before the keymap, after any
+    /// handlers added before it. See [`crate::key_handler`].
+    pub fn key_handler(mut self, handler: KeyHandler) -> AppBuilder {
+        self.key_handlers.push(handler);
+        self
+    }
+
+    /// Draw `segment` on the status bar, after the mode and any segments
+    /// added before it in the same place.
+    pub fn status_segment(mut self, place: Place, segment: Segment) -> AppBuilder {
+        self.status_segments.push((place, segment));
+        self
+    }
+
+    pub fn build(mut self) -> App {
+        if self.themes.is_empty() {
+            self.themes = vec![
+                ("default".to_string(), self.theme.clone()),
+                ("light".to_string(), Theme::light()),
+                ("mono".to_string(), Theme::monochrome()),
+            ];
+        }
+        if let Some(level) = self.config.colors {
+            let named = self.themes.iter_mut().map(|(_, theme)| theme);
+            for theme in self.tabs.iter_mut().filter_map(|t| t.theme.as_mut()).chain(named) {
+                *theme = theme.degrade(level);
+            }
+        }
+        if let Some(divider) = &self.config.divider {
+            self.theme.divider = divider.clone();
+            let named = self.themes.iter_mut().map(|(_, theme)| theme);
+            for theme in self.tabs.iter_mut().filter_map(|t| t.theme.as_mut()).chain(named) {
+                theme.divider = divider.clone();
+            }
+        }
+        let on_activate = self.on_activate
+            .or_else(|| self.config.exec.clone().map(activate::run_command));
+        if let Some(ms) = self.config.tick_rate {
+            self.tick_rate = Duration::from_millis(ms);
+        }
+        if let Some(ms) = self.config.poll_timeout {
+            self.poll_timeout = Duration::from_millis(ms);
+        }
+        for &(chord, action) in &self.config.bindings {
+            self.keymap.bind(chord, action);
+        }
+        let mut app = App::from_parts(self.config, self.theme, self.keymap, self.tick_rate, self.tabs);
+        app.poll_timeout = self.poll_timeout;
+        app.on_activate = on_activate;
+        app.key_handlers = self.key_handlers;
+        app.status_segments.extend(self.status_segments);
+        app.theme = self.themes[0].1.clone();
+        app.themes = self.themes;
+        if let Some(name) = app.config.theme.clone() {
+            if !app.select_theme(&name) {
+                app.set_error(format!("theme: no theme {}", name));
+            }
+        }
+        if let Some(on_copy) = self.on_copy {
+            app.on_copy = on_copy;
+        }
+        app.preview = app.config.preview.clone().map(Preview::new);
+        if self.on_tab_switch.is_some() {
+            app.on_tab_switch = self.on_tab_switch;
+            app.tab_switched();
+        }
+        if let Some(path) = app.config.init.clone() {
+            app.run_init(&path);
+        }
+        app
+    }
+}
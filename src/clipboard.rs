@@ -0,0 +1,58 @@
+//! Where text the user copies goes.
+//!
+//! There are two ways to copy the selected item: `c` copies its label alone,
+//! as it would be printed on selection, while `C` copies the row as it is
+//! drawn, with line numbers, check marks, icons, metadata and badges. Both
+//! hand the text to the app's [`OnCopy`].
+//!
+//! ```
+//! use std::{cell::RefCell, rc::Rc};
+//! use tui::{backend::TestBackend, Terminal};
+//! use tui_frontend::{run_app, App, Config, Item, ScriptedSource};
+//!
+//! let copied = Rc::new(RefCell::new(Vec::new()));
+//! let sink = Rc::clone(&copied);
+//! let mut app = App::builder()
+//!     .config(Config { line_numbers: true, ..Config::default() })
+//!     .items(vec![Item::new("apple", 1).with_meta("red")])
+//!     .on_copy(Box::new(move |text| Ok(sink.borrow_mut().push(text.to_string()))))
+//!     .build();
+//! let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+//! run_app(&mut terminal, &mut app, &mut ScriptedSource::keys("cCq")).unwrap();
+//! assert_eq!(copied.borrow()[0], "apple");
+//! assert!(copied.borrow()[1].starts_with("1 apple"));
+//! assert!(copied.borrow()[1].ends_with("red"));
+//! ```
+
+use std::io::{self, Write};
+
+/// Called with the copied text.
+pub type OnCopy = Box<dyn FnMut(&str) -> io::Result<()>>;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Ask the terminal to put the text on the system clipboard with an OSC 52
+/// escape sequence. This works over ssh, but some terminals ignore it or
+/// need it enabled.
+pub fn terminal() -> OnCopy {
+    Box::new(|text| {
+        let mut stdout = io::stdout();
+        write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+        stdout.flush()
+    })
+}
+
+fn base64(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
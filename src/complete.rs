@@ -0,0 +1,35 @@
+// Commands `:` understands, offered when completing their names
+const COMMANDS: &[&str] = &["filter", "reset", "sort", "tab", "write"];
+
+/// Completions of the last word of `input`, each as the whole line it
+/// would become. Only command names are completed so far.
+pub(crate) fn complete(input: &str) -> Vec<String> {
+    let word = input.trim_start();
+    if word.contains(char::is_whitespace) {
+        return Vec::new();
+    }
+    COMMANDS.iter()
+        .filter(|command| command.starts_with(word))
+        .map(|command| command.to_string())
+        .collect()
+}
+
+/// Completions being cycled through by repeated presses of Tab.
+pub(crate) struct Completion {
+    pub candidates: Vec<String>,
+    // Candidate the next Tab switches to
+    next: usize,
+}
+
+impl Completion {
+    pub fn new(candidates: Vec<String>) -> Completion {
+        Completion { candidates, next: 0 }
+    }
+
+    /// The next candidate, going back to the first after the last.
+    pub fn advance(&mut self) -> &str {
+        let candidate = &self.candidates[self.next];
+        self.next = (self.next + 1) % self.candidates.len();
+        candidate
+    }
+}
@@ -0,0 +1,397 @@
+use std::path::PathBuf;
+
+use crate::{activate::ActivateMode, keymap::{Action, Chord}, source::Source, theme::ColorLevel};
+
+/// How the binary prints the selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Just the label
+    Plain,
+    /// A JSON object with the label, payload, index and tab
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<OutputFormat, ()> {
+        match s {
+            "plain" => Ok(OutputFormat::Plain),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(()),
+        }
+    }
+}
+
+/// What to do with labels too long for the list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Cut them off at the edge of the list
+    Truncate,
+    /// Continue them on the following lines
+    Wrap,
+}
+
+/// Whether searching tells upper and lower case apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CaseSensitivity {
+    Insensitive,
+    Sensitive,
+    /// Like vim's `smartcase`: sensitive only once the query has an
+    /// uppercase letter
+    SmartCase,
+}
+
+impl CaseSensitivity {
+    /// Whether searching for `query` ignores case.
+    ///
+    /// ```
+    /// use tui_frontend::{App, CaseSensitivity, Config, Item};
+    ///
+    /// let labels = ["readme.md", "README.md", "ReadMe.md", "notes.txt"];
+    /// let mut app = App::builder()
+    ///     .config(Config { case: CaseSensitivity::SmartCase, ..Config::default() })
+    ///     .items(labels.iter().enumerate().map(|(i, label)| Item::new(*label, i)).collect())
+    ///     .build();
+    /// let mut matching = |query: &str| {
+    ///     app.query = query.to_string();
+    ///     app.apply_filter();
+    ///     app.list().visible().map(|item| item.label.clone()).collect::<Vec<_>>()
+    /// };
+    /// assert_eq!(matching("readme"), ["readme.md", "README.md", "ReadMe.md"]);
+    /// assert_eq!(matching("ReadMe"), ["ReadMe.md"]);
+    /// assert_eq!(matching("README"), ["README.md"]);
+    /// ```
+    pub fn ignores_case(self, query: &str) -> bool {
+        match self {
+            CaseSensitivity::Insensitive => true,
+            CaseSensitivity::Sensitive => false,
+            CaseSensitivity::SmartCase => !query.chars().any(char::is_uppercase),
+        }
+    }
+}
+
+impl std::str::FromStr for CaseSensitivity {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<CaseSensitivity, ()> {
+        match s {
+            "insensitive" => Ok(CaseSensitivity::Insensitive),
+            "sensitive" => Ok(CaseSensitivity::Sensitive),
+            "smart" => Ok(CaseSensitivity::SmartCase),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Where the tabs row sits relative to the list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TabPosition {
+    /// Above the list
+    Top,
+    /// Below the list, just above the status bar
+    Bottom,
+}
+
+impl std::str::FromStr for TabPosition {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<TabPosition, ()> {
+        match s {
+            "top" => Ok(TabPosition::Top),
+            "bottom" => Ok(TabPosition::Bottom),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Where the selection goes when streamed items are appended to the list.
+/// While following, the newest item is selected whatever this says.
+///
+/// ```
+/// use std::sync::mpsc;
+/// use tui::{backend::TestBackend, Terminal};
+/// use tui_frontend::{run_app, App, AppendSelection, Config, ScriptedSource, SortState, Stream};
+///
+/// for (on_append, expected) in [
+///     (AppendSelection::SameItem, "d"),
+///     (AppendSelection::SameIndex, "b"),
+///     (AppendSelection::Newest, "a"),
+/// ] {
+///     let (sender, receiver) = mpsc::channel();
+///     let mut app = App::builder()
+///         .config(Config { on_append, ..Config::default() })
+///         .stream_tab("log", Stream::new(receiver))
+///         .build();
+///     // Sorted by label, an appended item can land before the selection
+///     app.sort_state = SortState::LabelAsc;
+///     let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+///     sender.send("b".to_string()).unwrap();
+///     sender.send("d".to_string()).unwrap();
+///     run_app(&mut terminal, &mut app, &mut ScriptedSource::keys("q")).unwrap();
+///     app.list_mut().state.select(Some(1));
+///     assert_eq!(app.list().selected_item().unwrap().label, "d");
+///     sender.send("a".to_string()).unwrap();
+///     run_app(&mut terminal, &mut app, &mut ScriptedSource::keys("q")).unwrap();
+///     assert_eq!(app.list().selected_item().unwrap().label, expected);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppendSelection {
+    /// Stay on the selected item wherever it ends up
+    SameItem,
+    /// Stay at the same position in the list, on whichever item is there
+    SameIndex,
+    /// Move to the item appended last
+    Newest,
+}
+
+impl std::str::FromStr for AppendSelection {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<AppendSelection, ()> {
+        match s {
+            "item" => Ok(AppendSelection::SameItem),
+            "index" => Ok(AppendSelection::SameIndex),
+            "newest" => Ok(AppendSelection::Newest),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Settings controlling how the app looks and behaves.
+pub struct Config {
+    pub show_tabs: bool,
+    pub tab_position: TabPosition,
+    /// Blank lines rendered below each list item
+    pub row_spacing: u16,
+    /// Float recently selected items to the top and remember selections
+    pub mru: bool,
+    /// Command run on Enter instead of quitting, with `{}` replaced by the label
+    pub exec: Option<String>,
+    /// Show each item's position in a gutter left of its label
+    pub line_numbers: bool,
+    /// Style items the selection has visited differently, and remember them
+    pub track_visited: bool,
+    /// Overrides the theme's tab divider
+    pub divider: Option<String>,
+    /// Name of the theme to start with
+    pub theme: Option<String>,
+    /// Colors the terminal can show, which the themes are brought down to
+    pub colors: Option<ColorLevel>,
+    /// Whether searching tells upper and lower case apart
+    pub case: CaseSensitivity,
+    /// Search every field of an item rather than only its label
+    pub search_all_fields: bool,
+    /// Push labels written mostly right to left, such as Arabic or Hebrew,
+    /// to the far end of their rows, with the selection marker after them
+    pub rtl: bool,
+    pub output: OutputFormat,
+    /// Items to show instead of the demo data
+    pub source: Option<Source>,
+    /// Show the items side by side on a single line instead of in a list
+    pub bar: bool,
+    /// Color items along a green to red gradient by their payload
+    pub color_by_payload: bool,
+    pub overflow: Overflow,
+    /// Drop items beyond this many when loading
+    pub max_items: Option<usize>,
+    /// Title shown centered above everything else
+    pub banner: Option<String>,
+    /// Dim the contents of whichever of the list and details is not focused
+    pub dim_unfocused: bool,
+    /// Milliseconds between ticks, overriding the app's own tick rate
+    pub tick_rate: Option<u64>,
+    /// Longest the event loop waits for input before drawing again, in
+    /// milliseconds
+    pub poll_timeout: Option<u64>,
+    /// Empty rows and columns around the edge of the screen
+    pub margin: u16,
+    /// Highlight the first item on startup rather than waiting for the
+    /// first movement key
+    pub initial_selection: bool,
+    /// Remember search and command history between runs
+    pub save_history: bool,
+    /// Remember pinned items between runs
+    pub save_pins: bool,
+    /// Start with the theme chosen last time
+    pub save_theme: bool,
+    /// Show the payload right-aligned on rows without their own metadata
+    pub payload_column: bool,
+    /// Draw borders only around the focused pane
+    pub focus_borders_only: bool,
+    /// Give every other row of the list the theme's stripe background
+    pub stripes: bool,
+    /// Run the list down two columns side by side, a line per item
+    pub two_columns: bool,
+    /// Pass over tabs with nothing to select when moving between tabs
+    pub skip_empty_tabs: bool,
+    /// Ask before deleting items, clearing checks or reloading over edits
+    pub confirm_destructive: bool,
+    /// Show the command `exec` will run for an item and ask before running it
+    pub confirm_exec: bool,
+    /// Refuse every action that edits the items, leaving only browsing and
+    /// picking
+    pub read_only: bool,
+    /// Shell command whose output for the selected item fills the detail
+    /// pane, with `{}` replaced by the label
+    pub preview: Option<String>,
+    /// Show a footer with item counts and payload figures
+    pub stats: bool,
+    /// End each printed label with a NUL byte instead of a newline
+    pub print0: bool,
+    /// Quote printed labels for a POSIX shell
+    pub shell_quote: bool,
+    /// Bindings added to the keymap, replacing any for the same keys
+    pub bindings: Vec<(Chord, Action)>,
+    /// File of `:` commands run at startup, one per line
+    pub init: Option<PathBuf>,
+    /// Print the first frame as text and exit instead of running
+    pub dump: bool,
+    /// Draw on the terminal's alternate screen, leaving the scrollback
+    /// untouched. Terminals known to lack one are drawn on directly anyway
+    pub alternate_screen: bool,
+    /// Add the source's lines as they arrive rather than waiting for all of
+    /// them, reading stdin if there is no source
+    pub stream: bool,
+    /// Keep the newest streamed item selected until the selection is moved
+    /// off it
+    pub follow: bool,
+    /// Where the selection goes when streamed items are appended
+    pub on_append: AppendSelection,
+    /// Whether Enter ends the picker or keeps it open
+    pub activate_mode: ActivateMode,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            show_tabs: true,
+            tab_position: TabPosition::Top,
+            row_spacing: 0,
+            mru: false,
+            exec: None,
+            line_numbers: false,
+            track_visited: false,
+            divider: None,
+            theme: None,
+            colors: None,
+            case: CaseSensitivity::Insensitive,
+            search_all_fields: false,
+            rtl: false,
+            output: OutputFormat::Plain,
+            source: None,
+            bar: false,
+            color_by_payload: false,
+            overflow: Overflow::Truncate,
+            max_items: None,
+            banner: None,
+            dim_unfocused: true,
+            tick_rate: None,
+            poll_timeout: None,
+            margin: 1,
+            initial_selection: true,
+            save_history: false,
+            save_pins: false,
+            save_theme: false,
+            payload_column: false,
+            focus_borders_only: false,
+            stripes: false,
+            two_columns: false,
+            skip_empty_tabs: false,
+            confirm_destructive: false,
+            confirm_exec: false,
+            read_only: false,
+            preview: None,
+            stats: false,
+            print0: false,
+            shell_quote: false,
+            bindings: Vec::new(),
+            init: None,
+            dump: false,
+            alternate_screen: true,
+            stream: false,
+            follow: false,
+            on_append: AppendSelection::SameItem,
+            activate_mode: ActivateMode::SelectAndQuit,
+        }
+    }
+}
+
+impl Config {
+    /// Build a config from the process command line arguments.
+    pub fn from_args() -> Result<Config, String> {
+        let mut config = Config::default();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--no-tabs" => config.show_tabs = false,
+                "--line-numbers" => config.line_numbers = true,
+                "--track-read" => config.track_visited = true,
+                "--divider" => config.divider = Some(parse_value(&arg, args.next())?),
+                "--no-divider" => config.divider = Some(String::new()),
+                "--output" => config.output = parse_value(&arg, args.next())?,
+                "--file" => config.source = Some(Source::File(parse_value::<PathBuf>(&arg, args.next())?)),
+                "--command" => config.source = Some(Source::Command(parse_value(&arg, args.next())?)),
+                "--bar" => config.bar = true,
+                "--color-by-payload" => config.color_by_payload = true,
+                "--wrap" => config.overflow = Overflow::Wrap,
+                "--max-items" => config.max_items = Some(parse_value(&arg, args.next())?),
+                "--banner" => config.banner = Some(parse_value(&arg, args.next())?),
+                "--no-dim" => config.dim_unfocused = false,
+                "--tick-rate" => config.tick_rate = Some(parse_value(&arg, args.next())?),
+                "--poll-timeout" => config.poll_timeout = Some(parse_value(&arg, args.next())?),
+                "--margin" => config.margin = parse_value(&arg, args.next())?,
+                "--no-initial-selection" => config.initial_selection = false,
+                "--save-history" => config.save_history = true,
+                "--save-pins" => config.save_pins = true,
+                "--save-theme" => config.save_theme = true,
+                "--theme" => config.theme = Some(parse_value(&arg, args.next())?),
+                "--colors" => config.colors = Some(parse_value(&arg, args.next())?),
+                "--case" => config.case = parse_value(&arg, args.next())?,
+                "--search-all" => config.search_all_fields = true,
+                "--rtl" => config.rtl = true,
+                "--payload-column" => config.payload_column = true,
+                "--focus-borders" => config.focus_borders_only = true,
+                "--confirm" => config.confirm_destructive = true,
+                "--confirm-exec" => config.confirm_exec = true,
+                "--preview" => config.preview = Some(parse_value(&arg, args.next())?),
+                "--stats" => config.stats = true,
+                "--stripes" => config.stripes = true,
+                "--two-columns" => config.two_columns = true,
+                "--skip-empty-tabs" => config.skip_empty_tabs = true,
+                "--read-only" => config.read_only = true,
+                "--init" => config.init = Some(parse_value(&arg, args.next())?),
+                "--stay" => config.activate_mode = ActivateMode::Stay,
+                "--continue" => config.activate_mode = ActivateMode::SelectAndContinue,
+                "--dump" => config.dump = true,
+                "--no-alt-screen" => config.alternate_screen = false,
+                "--stream" => config.stream = true,
+                "--follow" => config.follow = true,
+                "--on-append" => config.on_append = parse_value(&arg, args.next())?,
+                "--bind" => config.bindings.push(parse_binding(&arg, args.next())?),
+                "--print0" => config.print0 = true,
+                "--shell-quote" => config.shell_quote = true,
+                "--tab-position" => config.tab_position = parse_value(&arg, args.next())?,
+                "--mru" => config.mru = true,
+                "--exec" => config.exec = Some(parse_value(&arg, args.next())?),
+                "--row-spacing" => config.row_spacing = parse_value(&arg, args.next())?,
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+// A binding given as KEY=ACTION, like ctrl-n=next-tab
+fn parse_binding(flag: &str, value: Option<String>) -> Result<(Chord, Action), String> {
+    let value = value.ok_or_else(|| format!("{} expects a value", flag))?;
+    let (key, action) = value.split_once('=')
+        .ok_or_else(|| format!("{} expects KEY=ACTION, got {}", flag, value))?;
+    Ok((key.parse()?, action.parse()?))
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} expects a value", flag))?;
+    value.parse().map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
@@ -0,0 +1,67 @@
+use std::{collections::VecDeque, io, time::Duration};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent};
+
+/// Input the event loop reacts to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppEvent {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+}
+
+impl AppEvent {
+    /// A press of `code` with no modifiers.
+    pub fn key(code: KeyCode) -> AppEvent {
+        AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+}
+
+/// Where the event loop gets its input from.
+pub trait EventSource {
+    /// Wait up to `timeout` for the next event. `None` means nothing
+    /// arrived in time, or something the app doesn't act on did.
+    fn next(&mut self, timeout: Duration) -> io::Result<Option<AppEvent>>;
+}
+
+/// Reads events from the terminal.
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn next(&mut self, timeout: Duration) -> io::Result<Option<AppEvent>> {
+        if !event::poll(timeout)? {
+            return Ok(None);
+        }
+        Ok(match event::read()? {
+            // Windows reports releases as well as presses; only act once per
+            // keystroke
+            Event::Key(key) if key.kind == KeyEventKind::Press => Some(AppEvent::Key(key)),
+            Event::Mouse(mouse) => Some(AppEvent::Mouse(mouse)),
+            _ => None,
+        })
+    }
+}
+
+/// Replays a fixed list of events, one per call, without waiting. Running
+/// out of events is an error, so a script that never quits can't hang.
+pub struct ScriptedSource {
+    events: VecDeque<AppEvent>,
+}
+
+impl ScriptedSource {
+    pub fn new(events: impl IntoIterator<Item = AppEvent>) -> ScriptedSource {
+        ScriptedSource { events: events.into_iter().collect() }
+    }
+
+    /// Key presses for each character of `keys`.
+    pub fn keys(keys: &str) -> ScriptedSource {
+        ScriptedSource::new(keys.chars().map(|c| AppEvent::key(KeyCode::Char(c))))
+    }
+}
+
+impl EventSource for ScriptedSource {
+    fn next(&mut self, _timeout: Duration) -> io::Result<Option<AppEvent>> {
+        match self.events.pop_front() {
+            Some(event) => Ok(Some(event)),
+            None => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "ran out of scripted events")),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::ops::Range;
+
+// Byte ranges of every non-overlapping occurrence of `query` in `text`
+pub(crate) fn match_ranges(text: &str, query: &str, ignore_case: bool) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    if query.is_empty() {
+        return ranges;
+    }
+    let mut start = 0;
+    while start < text.len() {
+        match match_at(&text[start..], query, ignore_case) {
+            Some(len) => {
+                ranges.push(start..start + len);
+                start += len;
+            }
+            None => start += text[start..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    ranges
+}
+
+// Split a query into words that must all appear in a label and words,
+// written with a leading `!`, that must not. A leading `\!` stands for a
+// literal `!`.
+fn parse_query(query: &str) -> (Vec<&str>, Vec<&str>) {
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    for word in query.split_whitespace() {
+        if let Some(word) = word.strip_prefix('\\').filter(|w| w.starts_with('!')) {
+            include.push(word);
+        } else if let Some(word) = word.strip_prefix('!') {
+            // A lone `!` excludes nothing
+            if !word.is_empty() {
+                exclude.push(word);
+            }
+        } else {
+            include.push(word);
+        }
+    }
+    (include, exclude)
+}
+
+pub(crate) fn matches(text: &str, query: &str, ignore_case: bool) -> bool {
+    let (include, exclude) = parse_query(query);
+    include.iter().all(|word| !match_ranges(text, word, ignore_case).is_empty())
+        && !exclude.iter().any(|word| !match_ranges(text, word, ignore_case).is_empty())
+}
+
+// Byte ranges of `text` to highlight for the words `query` looks for, in
+// order and with overlaps merged
+pub(crate) fn highlight_ranges(text: &str, query: &str, ignore_case: bool) -> Vec<Range<usize>> {
+    let (include, _) = parse_query(query);
+    let mut ranges: Vec<Range<usize>> = include.iter().flat_map(|word| match_ranges(text, word, ignore_case)).collect();
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+// Length in bytes of `text`'s prefix matching `query`
+fn match_at(text: &str, query: &str, ignore_case: bool) -> Option<usize> {
+    let mut chars = text.char_indices();
+    for q in query.chars() {
+        let (_, c) = chars.next()?;
+        let same = if ignore_case { c.to_lowercase().eq(q.to_lowercase()) } else { c == q };
+        if !same {
+            return None;
+        }
+    }
+    Some(chars.next().map_or(text.len(), |(i, _)| i))
+}
+
+/// Keeps the items whose payload it returns true for.
+pub(crate) type PayloadFilter = Box<dyn Fn(usize) -> bool>;
+
+// Parse a payload predicate: a comparison such as `>2`, `<=10` or `==3`, or
+// an inclusive range such as `2..5`
+pub(crate) fn parse_payload_filter(text: &str) -> Result<PayloadFilter, String> {
+    let text = text.trim();
+    let number = |s: &str| s.trim().parse::<usize>().map_err(|_| format!("not a number: {}", s.trim()));
+    if let Some((low, high)) = text.split_once("..") {
+        let (low, high) = (number(low)?, number(high.trim_start_matches('='))?);
+        return Ok(Box::new(move |p| (low..=high).contains(&p)));
+    }
+    let filter: PayloadFilter = if let Some(n) = text.strip_prefix(">=") {
+        let n = number(n)?;
+        Box::new(move |p| p >= n)
+    } else if let Some(n) = text.strip_prefix("<=") {
+        let n = number(n)?;
+        Box::new(move |p| p <= n)
+    } else if let Some(n) = text.strip_prefix("!=") {
+        let n = number(n)?;
+        Box::new(move |p| p != n)
+    } else if let Some(n) = text.strip_prefix('>') {
+        let n = number(n)?;
+        Box::new(move |p| p > n)
+    } else if let Some(n) = text.strip_prefix('<') {
+        let n = number(n)?;
+        Box::new(move |p| p < n)
+    } else {
+        let n = number(text.trim_start_matches('='))?;
+        Box::new(move |p| p == n)
+    };
+    Ok(filter)
+}
@@ -0,0 +1,472 @@
+use std::time::Instant;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+use crate::{activate::{self, ActivateMode, Activation}, app::{App, Mode, Pane, PendingConfirm}, key_handler, keymap::{Action, LEADER_TIMEOUT}, list::Selectable, model::Item};
+
+/// What the event loop should do after handling an event
+pub(crate) enum Flow {
+    Continue,
+    Quit,
+    Select,
+}
+
+// How deeply macros can replay one another, so one replaying itself ends
+const MACRO_DEPTH_LIMIT: usize = 20;
+
+pub(crate) fn handle_key(app: &mut App, key: KeyEvent) -> Flow {
+    // Keys replayed from a macro are already recorded as the key replaying it
+    if app.macro_depth == 0 {
+        if let Some((_, keys)) = &mut app.recording {
+            keys.push(key);
+        }
+    }
+    match app.mode {
+        Mode::Normal => handle_normal_key(app, key),
+        Mode::Command => {
+            handle_command_key(app, key);
+            Flow::Continue
+        }
+        Mode::Search => {
+            handle_search_key(app, key);
+            Flow::Continue
+        }
+        Mode::Visual => {
+            handle_visual_key(app, key);
+            Flow::Continue
+        }
+        Mode::Confirm => {
+            handle_confirm_key(app, key);
+            Flow::Continue
+        }
+        Mode::Hint => {
+            handle_hint_key(app, key);
+            Flow::Continue
+        }
+    }
+}
+
+// Letters narrow the hints down until one is typed in full. Anything that
+// isn't the start of a hint leaves hint mode
+fn handle_hint_key(app: &mut App, key: KeyEvent) {
+    let KeyCode::Char(c) = key.code else {
+        app.mode = Mode::Normal;
+        return;
+    };
+    app.hint_input.push(c);
+    let targets = app.hint_targets(&app.row_bounds);
+    if let Some(&(_, position)) = targets.iter().find(|(hint, _)| *hint == app.hint_input) {
+        app.list_mut().state.select(Some(position));
+        app.mode = Mode::Normal;
+    } else if !targets.iter().any(|(hint, _)| hint.starts_with(&app.hint_input)) {
+        app.set_status(format!("no hint {}", app.hint_input));
+        app.mode = Mode::Normal;
+    }
+}
+
+// Run `action` straight away, or once the user answers yes to `message` if
+// destructive actions are to be confirmed
+fn confirm(app: &mut App, message: impl Into<String>, action: impl FnOnce(&mut App) + 'static) {
+    if !app.config.confirm_destructive {
+        return action(app);
+    }
+    ask(app, message, action);
+}
+
+// Run `action` once the user answers yes to `message`
+fn ask(app: &mut App, message: impl Into<String>, action: impl FnOnce(&mut App) + 'static) {
+    app.pending_confirm = Some(PendingConfirm { message: message.into(), action: Box::new(action) });
+    app.mode = Mode::Confirm;
+}
+
+fn handle_confirm_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            app.mode = Mode::Normal;
+            if let Some(pending) = app.pending_confirm.take() {
+                (pending.action)(app);
+            }
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+            app.mode = Mode::Normal;
+            app.pending_confirm = None;
+        }
+        _ => {}
+    }
+}
+
+fn handle_normal_key(app: &mut App, key: KeyEvent) -> Flow {
+    // A key arriving after the leader resolves the sequence; once the
+    // sequence has timed out it is handled as a normal key again
+    if let Some(pressed) = app.pending_leader.take() {
+        if pressed.elapsed() < LEADER_TIMEOUT {
+            return match app.keymap.sequence(key) {
+                Some(action) => perform(app, action),
+                None => Flow::Continue,
+            };
+        }
+    }
+    if app.keymap.leader == Some(key.into()) {
+        app.pending_leader = Some(Instant::now());
+        return Flow::Continue;
+    }
+    if let Some(action) = app.pending_register.take() {
+        return match (action, key.code) {
+            (Action::Record, KeyCode::Char(register)) if register.is_ascii_alphanumeric() => {
+                app.recording = Some((register, Vec::new()));
+                Flow::Continue
+            }
+            (Action::Replay, KeyCode::Char(register)) => replay(app, register),
+            _ => Flow::Continue,
+        };
+    }
+    if key_handler::dispatch(app, key) {
+        return Flow::Continue;
+    }
+    match app.keymap.action(key) {
+        Some(action) => perform(app, action),
+        None if key.code == KeyCode::Esc => back_out(app),
+        None => Flow::Continue,
+    }
+}
+
+// Esc in normal mode undoes the first of these that applies: a pending
+// leader (handled above), whatever is running in the background, a
+// committed search filter, an opened list of children, then the selection,
+// and with nothing left to back out of it quits. The other modes handle Esc
+// themselves by returning to normal mode. Binding Esc in the keymap replaces
+// all of this.
+fn back_out(app: &mut App) -> Flow {
+    if app.cancel() {
+        return Flow::Continue;
+    }
+    if !app.query.is_empty() {
+        app.query.clear();
+        app.apply_filter();
+    } else if app.close_level() {
+        return Flow::Continue;
+    } else if app.list().state.selected().is_some() {
+        app.list_mut().unselect();
+    } else {
+        return Flow::Quit;
+    }
+    Flow::Continue
+}
+
+fn perform(app: &mut App, action: Action) -> Flow {
+    match action {
+        Action::Duplicate | Action::Delete | Action::Paste | Action::Undo if app.config.read_only => {
+            app.set_status("read-only")
+        }
+        // Quitting, with or without a pick, just abandons whatever is
+        // running, but reloading or activating an item while staying could
+        // change things under it
+        Action::Reload if app.busy => app.set_status("busy…"),
+        Action::Select if app.busy && app.on_activate.is_some() => app.set_status("busy…"),
+        Action::Quit => return Flow::Quit,
+        // With nothing picked yet, finish like Enter would have
+        Action::Done if app.picked.is_empty() => return Flow::Select,
+        Action::Done => return Flow::Quit,
+        Action::Select => return activate(app),
+        Action::Back => {
+            app.close_level();
+        }
+        Action::Cancel => {
+            if !app.cancel() {
+                app.set_status("nothing to cancel");
+            }
+        }
+        Action::Next if app.focus == Pane::Detail => app.detail_scroll = app.detail_scroll.saturating_add(1),
+        Action::Previous if app.focus == Pane::Detail => app.detail_scroll = app.detail_scroll.saturating_sub(1),
+        Action::Next => app.list_mut().next(),
+        Action::Previous => app.list_mut().previous(),
+        Action::Unselect => app.list_mut().unselect(),
+        Action::PageDown => app.page_down(),
+        Action::PageUp => app.page_up(),
+        Action::ScrollDown => app.scroll_half_page(true),
+        Action::ScrollUp => app.scroll_half_page(false),
+        // The bar lays items out horizontally, so left and right move along it
+        Action::NextTab if app.config.bar => app.list_mut().next(),
+        Action::PreviousTab if app.config.bar => app.list_mut().previous(),
+        Action::NextTab if app.config.show_tabs => app.next_tab(),
+        Action::PreviousTab if app.config.show_tabs => app.previous_tab(),
+        Action::GoToTab(i) if app.config.show_tabs => app.select_tab(i.min(app.tabs.items.len() - 1)),
+        Action::NextTab | Action::PreviousTab | Action::GoToTab(_) => {}
+        Action::Command => app.enter_command(""),
+        Action::Write => app.enter_command("write "),
+        Action::Search => app.enter_search(),
+        Action::ToggleLineNumbers => app.config.line_numbers = !app.config.line_numbers,
+        Action::Duplicate => app.duplicate_selected(),
+        Action::Yank => app.yank_selected(),
+        Action::CopyLabel => app.copy_selected(false),
+        Action::CopyDisplayed => app.copy_selected(true),
+        Action::Delete => {
+            if let Some(item) = app.list().selected_item() {
+                let message = format!("Delete {}?", item.label);
+                confirm(app, message, App::delete_selected);
+            }
+        }
+        Action::Paste => app.paste(),
+        Action::Undo => app.undo(),
+        Action::ToggleAllRead => app.toggle_all_read(),
+        // Edits can be undone, so only then is there anything to lose
+        Action::Reload if !app.undo.is_empty() => confirm(app, "Reload and lose your edits?", App::request_reload),
+        Action::Reload => app.request_reload(),
+        Action::ToggleCheck => app.list_mut().toggle_checked(),
+        Action::CheckAll => {
+            let visible = app.list().view.clone();
+            app.list_mut().check_all_visible(&visible);
+        }
+        Action::InvertChecked => {
+            let visible = app.list().view.clone();
+            app.list_mut().invert_checked(&visible);
+        }
+        Action::ClearChecked => {
+            let count = app.list().checked.len();
+            if count > 0 {
+                confirm(app, format!("Uncheck {} items?", count), |app| app.list_mut().clear_checked());
+            }
+        }
+        Action::NextChecked => app.list_mut().next_checked(),
+        Action::PreviousChecked => app.list_mut().previous_checked(),
+        Action::Visual => app.start_visual(),
+        Action::Hint => app.start_hints(),
+        Action::ToggleWrap => app.toggle_wrap(),
+        Action::ToggleBorders => app.config.focus_borders_only = !app.config.focus_borders_only,
+        Action::ToggleStripes => app.config.stripes = !app.config.stripes,
+        Action::ToggleZen => app.toggle_zen(),
+        Action::ToggleStay => app.toggle_activate_mode(),
+        Action::CycleTheme => app.cycle_theme(),
+        Action::Redraw => app.clear_requested = true,
+        Action::Follow => app.follow(),
+        Action::ToggleExpand => app.toggle_expanded(),
+        Action::CycleSort => app.cycle_sort(),
+        Action::TogglePin => app.toggle_pin(),
+        Action::Record => match app.recording.take() {
+            // The key stopping the recording isn't part of the macro
+            Some((register, mut keys)) => {
+                keys.pop();
+                app.macros.insert(register, keys);
+            }
+            None => app.pending_register = Some(Action::Record),
+        },
+        Action::Replay => app.pending_register = Some(Action::Replay),
+        // The details aren't shown in zen mode
+        Action::SwitchPane if app.zen => {}
+        Action::SwitchPane => {
+            app.focus = match app.focus {
+                Pane::List => Pane::Detail,
+                _ => Pane::List,
+            }
+        }
+    }
+    Flow::Continue
+}
+
+// Feed a macro's keys back through handle_key, stopping early if one of them
+// ends the event loop
+fn replay(app: &mut App, register: char) -> Flow {
+    let keys = match app.macros.get(&register) {
+        Some(keys) => keys.clone(),
+        None => return Flow::Continue,
+    };
+    if app.macro_depth >= MACRO_DEPTH_LIMIT {
+        app.set_error(format!("Macro @{} replays itself too deeply", register));
+        return Flow::Continue;
+    }
+    app.macro_depth += 1;
+    let mut flow = Flow::Continue;
+    for key in keys {
+        flow = handle_key(app, key);
+        if !matches!(flow, Flow::Continue) {
+            break;
+        }
+    }
+    app.macro_depth -= 1;
+    flow
+}
+
+fn activate(app: &mut App) -> Flow {
+    if app.open_children() {
+        return Flow::Continue;
+    }
+    let item = match app.list().selected_item() {
+        Some(item) => item.clone(),
+        None => return Flow::Continue,
+    };
+    // The exec command's line is shown in full before it runs. It never
+    // quits, so the flow after the answer is always to continue
+    if let (true, Some(command)) = (app.config.confirm_exec, &app.config.exec) {
+        let message = format!("Run {}?", activate::resolve_command(command, &item.label));
+        ask(app, message, move |app| {
+            run_activation(app, &item);
+        });
+        return Flow::Continue;
+    }
+    run_activation(app, &item)
+}
+
+fn run_activation(app: &mut App, item: &Item) -> Flow {
+    let activation = match &mut app.on_activate {
+        Some(on_activate) => on_activate(item),
+        None => Activation::Quit,
+    };
+    match activation {
+        Activation::Error(message) => {
+            app.set_error(message);
+            Flow::Continue
+        }
+        _ if app.config.activate_mode == ActivateMode::SelectAndContinue => {
+            app.mark_selected_visited();
+            app.pick();
+            Flow::Continue
+        }
+        Activation::Quit if app.config.activate_mode == ActivateMode::Stay => {
+            app.pick();
+            Flow::Continue
+        }
+        Activation::Quit => Flow::Select,
+        Activation::Stay => Flow::Continue,
+    }
+}
+
+// Only movement extends the range; anything else is ignored until the
+// range is confirmed or cancelled
+fn handle_visual_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => app.end_visual(false),
+        KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Char('V') => app.end_visual(true),
+        _ => match app.keymap.action(key) {
+            Some(Action::Next) => app.list_mut().next(),
+            Some(Action::Previous) => app.list_mut().previous(),
+            _ => {}
+        },
+    }
+}
+
+fn handle_command_key(app: &mut App, key: KeyEvent) {
+    if key.code != KeyCode::Tab {
+        app.completion = None;
+    }
+    match key.code {
+        KeyCode::Tab => app.complete_command(),
+        KeyCode::Esc => app.mode = Mode::Normal,
+        KeyCode::Enter => {
+            app.mode = Mode::Normal;
+            let line = app.input.lines()[0].clone();
+            app.command_history.push(&line);
+            app.run_command(&line);
+        }
+        KeyCode::Up => {
+            if let Some(line) = app.command_history.older().map(str::to_string) {
+                app.reset_input(&line);
+            }
+        }
+        KeyCode::Down => {
+            if let Some(line) = app.command_history.newer().map(str::to_string) {
+                app.reset_input(&line);
+            }
+        }
+        _ => {
+            app.input.input(key);
+        }
+    }
+}
+
+// The list is re-filtered after every edit, so deleting characters widens
+// the results just as typing narrows them
+fn handle_search_key(app: &mut App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.mode = Mode::Normal;
+            app.query.clear();
+            app.apply_filter();
+        }
+        KeyCode::Enter => {
+            app.mode = Mode::Normal;
+            let query = app.query.clone();
+            app.search_history.push(&query);
+        }
+        // Up and Down browse earlier searches, so moving through the results
+        // while typing is on Ctrl-p and Ctrl-n
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => app.list_mut().previous(),
+        KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => app.list_mut().next(),
+        KeyCode::Up | KeyCode::Down => {
+            let query = if key.code == KeyCode::Up {
+                app.search_history.older()
+            } else {
+                app.search_history.newer()
+            };
+            if let Some(query) = query.map(str::to_string) {
+                app.reset_input(&query);
+                app.sync_query();
+            }
+        }
+        _ => {
+            app.input.input(key);
+            app.sync_query();
+        }
+    }
+}
+
+// A shift-click checks every row between the previous click and this one
+fn click_row(app: &mut App, position: usize, shift: bool) {
+    let last_click = app.last_click;
+    let list = app.list_mut();
+    let index = list.view[position];
+    if !list.items[index].selectable() {
+        return;
+    }
+    match (shift, last_click) {
+        (true, Some(anchor)) if list.view.contains(&anchor) => {
+            list.anchor = Some(anchor);
+            list.state.select(Some(position));
+            list.check_range();
+        }
+        _ => list.state.select(Some(position)),
+    }
+    app.last_click = Some(index);
+}
+
+pub(crate) fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    // The divider is on the panes' borders, so it is checked before them
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) if app.on_divider(mouse.column, mouse.row) => {
+            app.dragging_divider = true;
+            return;
+        }
+        MouseEventKind::Drag(MouseButton::Left) if app.dragging_divider => {
+            app.drag_divider(mouse.column);
+            return;
+        }
+        MouseEventKind::Up(MouseButton::Left) if app.dragging_divider => {
+            app.dragging_divider = false;
+            return;
+        }
+        _ => {}
+    }
+    let pane = match app.pane_at(mouse.column, mouse.row) {
+        Some(pane) => pane,
+        None => return,
+    };
+    match (mouse.kind, pane) {
+        (MouseEventKind::Down(MouseButton::Left), Pane::Tabs) => {
+            if let Some(i) = app.tab_at(mouse.column, mouse.row) {
+                app.select_tab(i);
+            }
+        }
+        (MouseEventKind::Down(MouseButton::Left), Pane::List) => {
+            app.focus = Pane::List;
+            if let Some(position) = app.row_at(mouse.column, mouse.row) {
+                click_row(app, position, mouse.modifiers.contains(KeyModifiers::SHIFT));
+            }
+        }
+        (MouseEventKind::Down(MouseButton::Left), Pane::Detail) => app.focus = Pane::Detail,
+        (MouseEventKind::ScrollDown, Pane::Tabs) => app.next_tab(),
+        (MouseEventKind::ScrollUp, Pane::Tabs) => app.previous_tab(),
+        (MouseEventKind::ScrollDown, Pane::List) => app.list_mut().next(),
+        (MouseEventKind::ScrollUp, Pane::List) => app.list_mut().previous(),
+        (MouseEventKind::ScrollDown, Pane::Detail) => app.detail_scroll = app.detail_scroll.saturating_add(1),
+        (MouseEventKind::ScrollUp, Pane::Detail) => app.detail_scroll = app.detail_scroll.saturating_sub(1),
+        _ => {}
+    }
+}
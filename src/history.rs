@@ -0,0 +1,57 @@
+// Entries kept per history
+const HISTORY_LIMIT: usize = 100;
+
+/// Previously entered lines of a prompt, browsed with Up and Down.
+#[derive(Clone, Debug, Default)]
+pub struct History {
+    /// Oldest first
+    pub entries: Vec<String>,
+    // Entry currently shown while browsing
+    cursor: Option<usize>,
+}
+
+impl History {
+    pub fn new(entries: Vec<String>) -> History {
+        History { entries, cursor: None }
+    }
+
+    /// Add a committed line, skipping it if it repeats the last one, and
+    /// stop browsing.
+    pub fn push(&mut self, line: &str) {
+        self.cursor = None;
+        if line.is_empty() || self.entries.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.entries.push(line.to_string());
+        if self.entries.len() > HISTORY_LIMIT {
+            self.entries.remove(0);
+        }
+    }
+
+    /// The entry before the one shown, or the newest one when not browsing.
+    pub fn older(&mut self) -> Option<&str> {
+        let cursor = match self.cursor {
+            Some(0) => 0,
+            Some(i) => i - 1,
+            None => self.entries.len().checked_sub(1)?,
+        };
+        self.cursor = Some(cursor);
+        Some(&self.entries[cursor])
+    }
+
+    /// The entry after the one shown, or an empty line past the newest.
+    pub fn newer(&mut self) -> Option<&str> {
+        let cursor = self.cursor?;
+        if cursor + 1 < self.entries.len() {
+            self.cursor = Some(cursor + 1);
+            Some(&self.entries[cursor + 1])
+        } else {
+            self.cursor = None;
+            Some("")
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.cursor = None;
+    }
+}
@@ -0,0 +1,78 @@
+//! Keys that only mean something in some situations, tried before the
+//! keymap.
+//!
+//! Handlers are tried in the order they were added, and the first whose
+//! predicate holds takes the key: neither later handlers nor the keymap see
+//! it. Keys no handler takes fall through to the keymap as usual. Handlers
+//! only see keys in normal mode, after a leader sequence or a macro register
+//! waiting for its name has been resolved.
+
+use crossterm::event::KeyEvent;
+
+use crate::{app::App, keymap::Chord, model::Item};
+
+type Matches = Box<dyn Fn(&App, KeyEvent) -> bool>;
+type Run = Box<dyn FnMut(&mut App, KeyEvent)>;
+
+/// A key handler made of a predicate deciding whether it takes a key and
+/// what to do with the key when it does.
+pub struct KeyHandler {
+    matches: Matches,
+    run: Run,
+}
+
+impl KeyHandler {
+    pub fn new(
+        matches: impl Fn(&App, KeyEvent) -> bool + 'static,
+        run: impl FnMut(&mut App, KeyEvent) + 'static,
+    ) -> KeyHandler {
+        KeyHandler { matches: Box::new(matches), run: Box::new(run) }
+    }
+
+    /// Handle `key` whenever the selected item satisfies `predicate`.
+    ///
+    /// ```
+    /// use crossterm::event::KeyCode;
+    /// use tui::{backend::TestBackend, Terminal};
+    /// use tui_frontend::{run_app, App, Item, KeyHandler, ScriptedSource};
+    ///
+    /// // `o` checks only items that look like links
+    /// let links = KeyHandler::on_item(KeyCode::Char('o'), |item| item.label.starts_with("http"), |app| {
+    ///     app.list_mut().toggle_checked();
+    /// });
+    /// let mut app = App::builder()
+    ///     .items(vec![Item::new("https://example.com", 1), Item::new("notes.txt", 2)])
+    ///     .key_handler(links)
+    ///     .build();
+    /// let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+    /// run_app(&mut terminal, &mut app, &mut ScriptedSource::keys("ojoq")).unwrap();
+    /// assert_eq!(app.list().checked.len(), 1);
+    /// assert!(app.list().checked.contains(&0));
+    /// ```
+    pub fn on_item(
+        key: impl Into<Chord>,
+        predicate: impl Fn(&Item) -> bool + 'static,
+        mut run: impl FnMut(&mut App) + 'static,
+    ) -> KeyHandler {
+        let key = key.into();
+        KeyHandler::new(
+            move |app, pressed| Chord::from(pressed) == key && app.list().selected_item().is_some_and(&predicate),
+            move |app, _| run(app),
+        )
+    }
+}
+
+/// Give `key` to the first handler that takes it. Returns whether one did.
+pub(crate) fn dispatch(app: &mut App, key: KeyEvent) -> bool {
+    // The handlers are taken out while one runs so it can have the app
+    let mut handlers = std::mem::take(&mut app.key_handlers);
+    let handled = match handlers.iter_mut().find(|h| (h.matches)(app, key)) {
+        Some(handler) => {
+            (handler.run)(app, key);
+            true
+        }
+        None => false,
+    };
+    app.key_handlers = handlers;
+    handled
+}
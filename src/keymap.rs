@@ -0,0 +1,502 @@
+use std::{collections::HashMap, fmt, str::FromStr, time::Duration};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// How long after the leader key the second key of a sequence is awaited
+pub const LEADER_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Something a key can be bound to in normal mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    /// Confirm the highlighted item and exit, or open its children if it
+    /// has any
+    Select,
+    /// Go back up to the list the shown one was opened from
+    Back,
+    /// Stop what's running in the background
+    Cancel,
+    /// End the picker with the items picked so far, or the selection if
+    /// nothing was picked
+    Done,
+    Next,
+    Previous,
+    Unselect,
+    /// Scroll a screen down, accounting for wrapped items' heights
+    PageDown,
+    PageUp,
+    /// Scroll half a screen, keeping the selection where it is if it
+    /// stays on screen
+    ScrollDown,
+    ScrollUp,
+    NextTab,
+    PreviousTab,
+    /// Switch to the tab at this index, or the last one if there are fewer.
+    /// Bound to `1`–`9` by default.
+    ///
+    /// ```
+    /// use crossterm::event::KeyCode;
+    /// use tui::{backend::TestBackend, Terminal};
+    /// use tui_frontend::{run_app, App, AppEvent, Item, ScriptedSource};
+    ///
+    /// let mut app = App::builder()
+    ///     .tab("one", vec![Item::new("a", 1)])
+    ///     .tab("two", vec![Item::new("b", 2)])
+    ///     .tab("three", vec![Item::new("c", 3)])
+    ///     .build();
+    /// let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+    /// // Digits past the last tab go to the last one
+    /// for (digit, title) in [('2', "two"), ('1', "one"), ('3', "three"), ('1', "one"), ('9', "three"), ('4', "three")] {
+    ///     let _ = run_app(&mut terminal, &mut app, &mut ScriptedSource::new([AppEvent::key(KeyCode::Char(digit))]));
+    ///     assert_eq!(app.active_tab().title, title, "after {}", digit);
+    /// }
+    /// ```
+    GoToTab(usize),
+    /// Open the `:` command prompt
+    Command,
+    /// Open the command prompt prefilled with `write `
+    Write,
+    Search,
+    ToggleLineNumbers,
+    /// Insert a copy of the selected item after it
+    Duplicate,
+    /// Copy the selected item into the register
+    Yank,
+    /// Copy the selected item's label to the clipboard
+    CopyLabel,
+    /// Copy the selected row as drawn, decorations and all
+    CopyDisplayed,
+    /// Remove the selected item into the register
+    Delete,
+    /// Insert the register's item after the selection
+    Paste,
+    Undo,
+    /// Mark every item read, or unread if they all are already
+    ToggleAllRead,
+    /// Load the active tab's items from their source again
+    Reload,
+    /// Check or uncheck the selected item
+    ToggleCheck,
+    /// Check every item matching the filter
+    CheckAll,
+    /// Check the unchecked items matching the filter and uncheck the checked
+    InvertChecked,
+    /// Uncheck every item, including those the filter hides
+    ClearChecked,
+    NextChecked,
+    PreviousChecked,
+    /// Start checking a range of items from the selection
+    Visual,
+    /// Label the rows on screen with letters to type to jump to one
+    Hint,
+    /// Switch between wrapping and truncating long labels
+    ToggleWrap,
+    /// Move focus between the list and the details
+    SwitchPane,
+    /// Switch between borders around every pane and only the focused one
+    ToggleBorders,
+    /// Turn striping of alternate rows on or off
+    ToggleStripes,
+    /// Switch between showing only the list and the full layout
+    ToggleZen,
+    /// Cycle between quitting on Enter, staying open to pick more and
+    /// picking each activated item
+    ToggleStay,
+    /// Switch to the next of the app's named themes
+    CycleTheme,
+    /// Clear the terminal and draw everything again, bound to Ctrl-l
+    Redraw,
+    /// Select the last item and keep the newest one selected as items
+    /// stream in
+    Follow,
+    /// Show the selected item's details beneath it in the list
+    ToggleExpand,
+    /// Switch between sorting by label, by payload and the original order
+    CycleSort,
+    /// Keep the selected item at the top of the list, or stop keeping it
+    /// there
+    TogglePin,
+    /// Start recording keys into the register named by the next key, or
+    /// stop recording
+    Record,
+    /// Replay the keys recorded into the register named by the next key.
+    /// The screen isn't drawn between replayed keys, so those that hint or
+    /// scroll after a filter see the rows of the last frame.
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use tui::{backend::TestBackend, Terminal};
+    /// use tui_frontend::{run_app, App, AppEvent, Item, ScriptedSource};
+    ///
+    /// let items = || (0..100).map(|i| Item::new(if i == 50 { "x".to_string() } else { format!("item {i}") }, i)).collect();
+    /// let char = |c| AppEvent::key(KeyCode::Char(c));
+    /// let enter = AppEvent::key(KeyCode::Enter);
+    /// let esc = AppEvent::key(KeyCode::Esc);
+    /// let end = AppEvent::key(KeyCode::End);
+    /// let ctrl_u = AppEvent::Key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+    /// let sequences = [
+    ///     // Filtering then picking by hint
+    ///     (vec![char('Q'), char('a'), char('/'), char('x'), enter, char('g'), char('a'), char('Q')],
+    ///      vec![char('/'), esc, char('@'), char('a')]),
+    ///     // Filtering then scrolling from the end of the list
+    ///     (vec![end, char('Q'), char('a'), char('/'), char('x'), enter, ctrl_u, char('Q')],
+    ///      vec![char('/'), esc, end, char('@'), char('a')]),
+    /// ];
+    /// for (record, replay) in sequences {
+    ///     let mut app = App::builder().items(items()).build();
+    ///     let mut terminal = Terminal::new(TestBackend::new(40, 16)).unwrap();
+    ///     for event in record.into_iter().chain(replay) {
+    ///         let _ = run_app(&mut terminal, &mut app, &mut ScriptedSource::new([event]));
+    ///     }
+    ///     assert_eq!(app.list().selected_item().unwrap().label, "x");
+    /// }
+    /// ```
+    Replay,
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    /// Parse an action's name in kebab case, like `next-tab`, or `tab-N`
+    /// for [`Action::GoToTab`] counting from 1.
+    fn from_str(s: &str) -> Result<Action, String> {
+        if let Some(n) = s.strip_prefix("tab-") {
+            return match n.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(Action::GoToTab(n - 1)),
+                _ => Err(format!("invalid tab number: {}", n)),
+            };
+        }
+        Ok(match s {
+            "quit" => Action::Quit,
+            "select" => Action::Select,
+            "back" => Action::Back,
+            "cancel" => Action::Cancel,
+            "done" => Action::Done,
+            "next" => Action::Next,
+            "previous" => Action::Previous,
+            "unselect" => Action::Unselect,
+            "page-down" => Action::PageDown,
+            "page-up" => Action::PageUp,
+            "scroll-down" => Action::ScrollDown,
+            "scroll-up" => Action::ScrollUp,
+            "next-tab" => Action::NextTab,
+            "previous-tab" => Action::PreviousTab,
+            "command" => Action::Command,
+            "write" => Action::Write,
+            "search" => Action::Search,
+            "toggle-line-numbers" => Action::ToggleLineNumbers,
+            "duplicate" => Action::Duplicate,
+            "yank" => Action::Yank,
+            "copy-label" => Action::CopyLabel,
+            "copy-displayed" => Action::CopyDisplayed,
+            "delete" => Action::Delete,
+            "paste" => Action::Paste,
+            "undo" => Action::Undo,
+            "toggle-all-read" => Action::ToggleAllRead,
+            "reload" => Action::Reload,
+            "toggle-check" => Action::ToggleCheck,
+            "check-all" => Action::CheckAll,
+            "invert-checked" => Action::InvertChecked,
+            "clear-checked" => Action::ClearChecked,
+            "next-checked" => Action::NextChecked,
+            "previous-checked" => Action::PreviousChecked,
+            "visual" => Action::Visual,
+            "hint" => Action::Hint,
+            "toggle-wrap" => Action::ToggleWrap,
+            "switch-pane" => Action::SwitchPane,
+            "toggle-borders" => Action::ToggleBorders,
+            "toggle-stripes" => Action::ToggleStripes,
+            "toggle-zen" => Action::ToggleZen,
+            "toggle-stay" => Action::ToggleStay,
+            "cycle-theme" => Action::CycleTheme,
+            "redraw" => Action::Redraw,
+            "follow" => Action::Follow,
+            "toggle-expand" => Action::ToggleExpand,
+            "cycle-sort" => Action::CycleSort,
+            "toggle-pin" => Action::TogglePin,
+            "record" => Action::Record,
+            "replay" => Action::Replay,
+            _ => return Err(format!("unknown action: {}", s)),
+        })
+    }
+}
+
+/// A key together with the modifiers held down with it. Shift is folded into
+/// characters, since terminals report shifted ones already uppercased, so
+/// `Shift-n` is the same chord as `N`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Chord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl Chord {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Chord {
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::Char(c.to_ascii_uppercase()),
+            code => code,
+        };
+        let modifiers = match code {
+            // Shift-Tab is reported as BackTab, with or without Shift
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers - KeyModifiers::SHIFT,
+            _ => modifiers,
+        };
+        Chord { code, modifiers }
+    }
+}
+
+impl From<KeyCode> for Chord {
+    fn from(code: KeyCode) -> Chord {
+        Chord::new(code, KeyModifiers::NONE)
+    }
+}
+
+impl From<KeyEvent> for Chord {
+    fn from(key: KeyEvent) -> Chord {
+        Chord::new(key.code, key.modifiers)
+    }
+}
+
+impl FromStr for Chord {
+    type Err = String;
+
+    /// Parse a key name with any of the prefixes `Ctrl-`, `Alt-` and
+    /// `Shift-`, in any case.
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyModifiers};
+    /// use tui_frontend::Chord;
+    ///
+    /// let chord: Chord = "Ctrl-Shift-n".parse().unwrap();
+    /// assert_eq!(chord, Chord::new(KeyCode::Char('N'), KeyModifiers::CONTROL));
+    /// assert_eq!("alt-pagedown".parse(), Ok(Chord::new(KeyCode::PageDown, KeyModifiers::ALT)));
+    /// assert_eq!("-".parse(), Ok(Chord::from(KeyCode::Char('-'))));
+    /// assert!("Ctrl-".parse::<Chord>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Chord, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // A trailing dash is the key itself rather than a separator
+        while let Some((prefix, key)) = rest.split_once('-').filter(|(_, key)| !key.is_empty()) {
+            modifiers |= match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            rest = key;
+        }
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "insert" => KeyCode::Insert,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n) => KeyCode::F(n),
+                    None => return Err(format!("unknown key: {}", s)),
+                },
+            },
+        };
+        Ok(Chord::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt-")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift-")?;
+        }
+        write!(f, "{}", key_name(self.code))
+    }
+}
+
+/// Normal mode key bindings.
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    bindings: HashMap<Chord, Action>,
+    /// Key starting a two-key sequence
+    pub leader: Option<Chord>,
+    /// Actions for the key pressed after the leader
+    sequences: HashMap<Chord, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        let mut keymap = Keymap::empty();
+        keymap.bind(KeyCode::Char('q'), Action::Quit);
+        keymap.bind(KeyCode::Enter, Action::Select);
+        keymap.bind(Chord::new(KeyCode::Enter, KeyModifiers::CONTROL), Action::Done);
+        keymap.bind(KeyCode::Char('D'), Action::Done);
+        keymap.bind(KeyCode::Down, Action::Next);
+        keymap.bind(KeyCode::Up, Action::Previous);
+        keymap.bind(KeyCode::Char('j'), Action::Next);
+        keymap.bind(KeyCode::Char('k'), Action::Previous);
+        keymap.bind(KeyCode::Tab, Action::Next);
+        keymap.bind(KeyCode::Char('u'), Action::Unselect);
+        keymap.bind(KeyCode::Char('h'), Action::Back);
+        keymap.bind(KeyCode::PageDown, Action::PageDown);
+        keymap.bind(KeyCode::PageUp, Action::PageUp);
+        keymap.bind(Chord::new(KeyCode::Char('d'), KeyModifiers::CONTROL), Action::ScrollDown);
+        keymap.bind(Chord::new(KeyCode::Char('u'), KeyModifiers::CONTROL), Action::ScrollUp);
+        keymap.bind(Chord::new(KeyCode::Char('l'), KeyModifiers::CONTROL), Action::Redraw);
+        keymap.bind(Chord::new(KeyCode::Char('a'), KeyModifiers::CONTROL), Action::CheckAll);
+        keymap.bind(Chord::new(KeyCode::Char('c'), KeyModifiers::CONTROL), Action::Cancel);
+        keymap.bind(KeyCode::End, Action::Follow);
+        keymap.bind(KeyCode::Char('F'), Action::Follow);
+        keymap.bind(KeyCode::Left, Action::PreviousTab);
+        keymap.bind(KeyCode::Right, Action::NextTab);
+        for (i, c) in ('1'..='9').enumerate() {
+            keymap.bind(KeyCode::Char(c), Action::GoToTab(i));
+        }
+        keymap.bind(KeyCode::Char(':'), Action::Command);
+        keymap.bind(KeyCode::Char('W'), Action::Write);
+        keymap.bind(KeyCode::Char('/'), Action::Search);
+        keymap.bind(KeyCode::Char('#'), Action::ToggleLineNumbers);
+        keymap.bind(KeyCode::Char('P'), Action::Duplicate);
+        keymap.bind(KeyCode::Char('y'), Action::Yank);
+        keymap.bind(KeyCode::Char('c'), Action::CopyLabel);
+        keymap.bind(KeyCode::Char('C'), Action::CopyDisplayed);
+        keymap.bind(KeyCode::Char('d'), Action::Delete);
+        keymap.bind(KeyCode::Char('p'), Action::Paste);
+        keymap.bind(KeyCode::Char('U'), Action::Undo);
+        keymap.bind(KeyCode::Char('R'), Action::ToggleAllRead);
+        keymap.bind(KeyCode::Char('r'), Action::Reload);
+        keymap.bind(KeyCode::F(5), Action::Reload);
+        keymap.bind(KeyCode::Char('x'), Action::ToggleCheck);
+        keymap.bind(KeyCode::Char('V'), Action::Visual);
+        keymap.bind(KeyCode::Char('g'), Action::Hint);
+        keymap.bind(KeyCode::Char('a'), Action::CheckAll);
+        keymap.bind(KeyCode::Char('A'), Action::InvertChecked);
+        keymap.bind(KeyCode::Char('X'), Action::ClearChecked);
+        keymap.bind(KeyCode::Char(']'), Action::NextChecked);
+        keymap.bind(KeyCode::Char('['), Action::PreviousChecked);
+        keymap.bind(KeyCode::Char('w'), Action::ToggleWrap);
+        keymap.bind(KeyCode::BackTab, Action::SwitchPane);
+        keymap.bind(KeyCode::Char('b'), Action::ToggleBorders);
+        keymap.bind(KeyCode::Char('z'), Action::ToggleStripes);
+        keymap.bind(KeyCode::Char('Z'), Action::ToggleZen);
+        keymap.bind(KeyCode::Char('S'), Action::ToggleStay);
+        keymap.bind(KeyCode::Char('T'), Action::CycleTheme);
+        keymap.bind(KeyCode::Char('i'), Action::ToggleExpand);
+        keymap.bind(KeyCode::Char('s'), Action::CycleSort);
+        keymap.bind(KeyCode::Char('m'), Action::TogglePin);
+        keymap.bind(KeyCode::Char('Q'), Action::Record);
+        keymap.bind(KeyCode::Char('@'), Action::Replay);
+        keymap.leader = Some(KeyCode::Char(' ').into());
+        keymap.bind_sequence(KeyCode::Char('f'), Action::Search);
+        keymap.bind_sequence(KeyCode::Char('w'), Action::Write);
+        keymap.bind_sequence(KeyCode::Char(':'), Action::Command);
+        keymap
+    }
+}
+
+impl Keymap {
+    /// A keymap with no bindings at all.
+    pub fn empty() -> Keymap {
+        Keymap { bindings: HashMap::new(), leader: None, sequences: HashMap::new() }
+    }
+
+    /// Bind a key, bare or as a [`Chord`] with modifiers.
+    pub fn bind(&mut self, key: impl Into<Chord>, action: Action) {
+        self.bindings.insert(key.into(), action);
+    }
+
+    pub fn unbind(&mut self, key: impl Into<Chord>) {
+        self.bindings.remove(&key.into());
+    }
+
+    /// The action bound to a key, which only matches a binding with exactly
+    /// the same modifiers.
+    ///
+    /// ```
+    /// use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    /// use tui_frontend::{Action, Chord, Keymap};
+    ///
+    /// let mut keymap = Keymap::empty();
+    /// keymap.bind(KeyCode::Char('n'), Action::Next);
+    /// keymap.bind("Ctrl-Shift-n".parse::<Chord>().unwrap(), Action::NextTab);
+    /// assert_eq!(keymap.action(KeyCode::Char('n')), Some(Action::Next));
+    /// let pressed = KeyEvent::new(KeyCode::Char('N'), KeyModifiers::CONTROL | KeyModifiers::SHIFT);
+    /// assert_eq!(keymap.action(pressed), Some(Action::NextTab));
+    /// assert_eq!(keymap.action(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::ALT)), None);
+    /// ```
+    pub fn action(&self, key: impl Into<Chord>) -> Option<Action> {
+        self.bindings.get(&key.into()).copied()
+    }
+
+    /// Bind `key` pressed right after the leader key.
+    pub fn bind_sequence(&mut self, key: impl Into<Chord>, action: Action) {
+        self.sequences.insert(key.into(), action);
+    }
+
+    /// Action for `key` pressed within [`LEADER_TIMEOUT`] of the leader. A
+    /// key without one is swallowed, while a key arriving later is handled
+    /// as if the leader was never pressed.
+    ///
+    /// ```
+    /// use std::thread;
+    /// use crossterm::event::KeyCode;
+    /// use tui::{backend::TestBackend, Terminal};
+    /// use tui_frontend::{run_app, App, AppEvent, Item, Mode, ScriptedSource, LEADER_TIMEOUT};
+    ///
+    /// let mut app = App::builder()
+    ///     .items(vec![Item::new("apple", 1), Item::new("pear", 2), Item::new("plum", 3)])
+    ///     .build();
+    /// assert!(app.keymap.sequence(KeyCode::Char('f')).is_some());
+    /// assert!(app.keymap.sequence(KeyCode::Char('j')).is_none());
+    /// let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+    /// let mut press = |app: &mut App, keys: &[KeyCode]| {
+    ///     let events = keys.iter().map(|&key| AppEvent::key(key));
+    ///     let _ = run_app(&mut terminal, app, &mut ScriptedSource::new(events));
+    /// };
+    ///
+    /// // Space f starts a search
+    /// press(&mut app, &[KeyCode::Char(' '), KeyCode::Char('f')]);
+    /// assert!(app.mode == Mode::Search);
+    /// press(&mut app, &[KeyCode::Esc]);
+    ///
+    /// // Space j isn't bound, so j doesn't move the selection either
+    /// press(&mut app, &[KeyCode::Char(' '), KeyCode::Char('j')]);
+    /// assert!(app.mode == Mode::Normal);
+    /// assert_eq!(app.list().selected_item().unwrap().label, "apple");
+    ///
+    /// // Once the leader has timed out, j moves down again
+    /// press(&mut app, &[KeyCode::Char(' ')]);
+    /// thread::sleep(LEADER_TIMEOUT);
+    /// press(&mut app, &[KeyCode::Char('j')]);
+    /// assert_eq!(app.list().selected_item().unwrap().label, "pear");
+    /// ```
+    pub fn sequence(&self, key: impl Into<Chord>) -> Option<Action> {
+        self.sequences.get(&key.into()).copied()
+    }
+}
+
+/// Human readable name of a key, as shown in the status bar.
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        other => format!("{:?}", other),
+    }
+}
@@ -0,0 +1,393 @@
+//! A small terminal list picker built on `tui`.
+//!
+//! The binary is a thin wrapper around [`run`]; [`run_with`] runs a picker
+//! over your own data to embed it in another program:
+//!
+//! ```no_run
+//! use tui_frontend::{run_with, App, Item};
+//!
+//! fn main() -> Result<(), tui_frontend::AppError> {
+//!     let app = App::builder()
+//!         .items(vec![Item::new("apple", 1), Item::new("pear", 2)])
+//!         .build();
+//!     for selection in run_with(app)? {
+//!         println!("picked {} ({})", selection.label, selection.payload);
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use std::{fmt, io, time::{Duration, Instant}};
+use unicode_width::UnicodeWidthStr;
+use tui::{backend::{Backend, CrosstermBackend, TestBackend}, buffer::Buffer, Terminal};
+use crossterm::{
+    cursor::MoveTo,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    style::Print,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+pub mod activate;
+mod app;
+mod bidi;
+mod builder;
+pub mod clipboard;
+mod complete;
+mod config;
+mod event;
+mod filter;
+mod handler;
+mod history;
+pub mod key_handler;
+mod keymap;
+mod list;
+mod model;
+mod paths;
+mod preview;
+mod source;
+mod spec;
+mod stream;
+mod state;
+pub mod status;
+pub mod tab_switch;
+mod theme;
+mod ui;
+
+pub use activate::{ActivateMode, Activation, OnActivate};
+pub use app::{App, Mode, Pane, SortState, Stats};
+pub use builder::AppBuilder;
+pub use config::{AppendSelection, CaseSensitivity, Config, OutputFormat, Overflow, TabPosition};
+pub use event::{AppEvent, EventSource, ScriptedSource, TerminalEvents};
+pub use history::History;
+pub use key_handler::KeyHandler;
+pub use keymap::{key_name, Action, Chord, Keymap, LEADER_TIMEOUT};
+pub use list::{Selectable, StatefulList};
+pub use model::{Item, Level, Tab, TabState};
+pub use preview::{Preview, PreviewState};
+pub use source::Source;
+pub use spec::{ItemSpec, Spec, StyleSpec, TabSpec};
+pub use stream::Stream;
+pub use tab_switch::OnTabSwitch;
+pub use theme::{ColorLevel, Theme};
+
+use handler::Flow;
+use state::State;
+
+/// The item chosen when the user confirmed a selection.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Selection {
+    /// Position of the item in its list
+    pub index: usize,
+    pub label: String,
+    pub payload: usize,
+    /// Title of the tab the item is in
+    pub tab: String,
+}
+
+#[derive(Debug)]
+pub enum AppError {
+    Io(io::Error),
+    /// The configured source couldn't be loaded
+    Source(io::Error),
+    /// There is no interactive terminal to put into raw mode
+    NoTerminal(io::Error),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::Io(err) => write!(f, "terminal error: {}", err),
+            AppError::Source(err) => write!(f, "could not load items: {}", err),
+            AppError::NoTerminal(err) => write!(f, "an interactive terminal is needed, but it could not be set up: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::Io(err) | AppError::Source(err) | AppError::NoTerminal(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(err: io::Error) -> AppError {
+        AppError::Io(err)
+    }
+}
+
+/// Run a picker over the configured source, or the demo data if there is
+/// none, until the user quits.
+///
+/// Returns the confirmed selections: every checked item, or the highlighted
+/// one if none are checked. The list is empty if the user quit without
+/// choosing anything.
+pub fn run(mut config: Config) -> Result<Vec<Selection>, AppError> {
+    if config.colors.is_none() {
+        config.colors = Some(ColorLevel::detect());
+    }
+    let app = match config.source.clone() {
+        // Streamed items are added as they arrive, from stdin if there's no
+        // source
+        _ if config.stream => {
+            let (title, stream) = match &config.source {
+                Some(Source::File(path)) => (path.display().to_string(), Stream::file(path).map_err(AppError::Source)?),
+                Some(Source::Command(command)) => (command.clone(), Stream::command(command).map_err(AppError::Source)?),
+                None => ("stdin".to_string(), Stream::stdin()),
+            };
+            App::builder().config(config).stream_tab(title, stream).build()
+        }
+        // A JSON file describes the tabs and items rather than listing them
+        Some(Source::File(path)) if path.extension().is_some_and(|extension| extension == "json") => {
+            let spec = Spec::load(&path).map_err(AppError::Source)?;
+            App::builder().config(config).spec(&spec).build()
+        }
+        // Rather than showing the YAML a line at a time as if it were a list
+        Some(Source::File(path)) if path.extension().is_some_and(|extension| extension == "yaml" || extension == "yml") => {
+            let message = format!("{}: YAML specs aren't supported, write the spec as JSON", path.display());
+            return Err(AppError::Source(io::Error::new(io::ErrorKind::Unsupported, message)));
+        }
+        Some(source) => {
+            let (items, invalid) = source.load_lossy().map_err(AppError::Source)?;
+            let mut app = App::builder()
+                .config(config)
+                .source_tab(source.title(), items, source)
+                .build();
+            if invalid > 0 {
+                app.set_status(app::invalid_utf8_message(invalid));
+            }
+            app
+        }
+        None => App::new(config),
+    };
+    run_with(app)
+}
+
+/// Run a picker built with [`App::builder`] until the user quits.
+pub fn run_with(mut app: App) -> Result<Vec<Selection>, AppError> {
+    let persist = app.config.mru || app.config.track_visited || app.config.save_history || app.config.save_pins
+        || app.config.save_theme;
+    let mut state = if persist { Some(State::load()) } else { None };
+    if let Some(state) = &state {
+        app.restore(state);
+    }
+
+    if app.config.dump {
+        // Without a terminal to measure, fall back to the classic size
+        let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+        select_initial(&mut app);
+        app.mark_selected_visited();
+        print!("{}", dump(&mut app, width, height));
+        return Ok(Vec::new());
+    }
+
+    // Set up terminal properties for ui
+    enable_raw_mode().map_err(AppError::NoTerminal)?;
+    let mut stdout = io::stdout();
+    // Without the alternate screen, or if the terminal refuses to switch to
+    // it, the picker is drawn over the main screen instead
+    let alternate = app.config.alternate_screen && has_alternate_screen()
+        && execute!(stdout, EnterAlternateScreen).is_ok();
+    // Don't leave the shell in raw mode, or on the alternate screen, if the
+    // rest of the setup fails
+    let setup = if alternate { execute!(stdout, EnableMouseCapture) } else { make_room(&mut stdout) };
+    if let Err(err) = setup {
+        let _ = disable_raw_mode();
+        if alternate {
+            let _ = execute!(stdout, LeaveAlternateScreen);
+        }
+        return Err(AppError::NoTerminal(err));
+    }
+    let backend = CrosstermBackend::new(stdout);
+    let mut terminal = Terminal::new(backend)?;
+
+    let res = run_app(&mut terminal, &mut app, &mut TerminalEvents);
+
+    // Restore terminal back to previous state
+    disable_raw_mode()?;
+    if alternate {
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    } else {
+        // Leave an empty screen where the picker was, with the shell at its top
+        terminal.clear()?;
+        execute!(terminal.backend_mut(), MoveTo(0, 0))?;
+    }
+    execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    terminal.show_cursor()?;
+
+    let selections = res?;
+    if let Some(state) = &mut state {
+        app.persist(state, &selections);
+        if let Err(err) = state.save() {
+            eprintln!("warning: could not save state: {}", err);
+        }
+    }
+    Ok(selections)
+}
+
+// Terminals whose terminfo entries have no alternate screen. Others may
+// still lack one without saying so, which is what `--no-alt-screen` is for
+fn has_alternate_screen() -> bool {
+    let term = std::env::var("TERM").unwrap_or_default();
+    !matches!(term.as_str(), "dumb" | "linux" | "vt100" | "vt102" | "vt220")
+}
+
+// Scroll what's on the main screen up into the scrollback so the picker
+// doesn't draw over it
+fn make_room(stdout: &mut io::Stdout) -> io::Result<()> {
+    let (_, height) = crossterm::terminal::size()?;
+    execute!(stdout, MoveTo(0, height.saturating_sub(1)), Print("\n".repeat(height as usize)), EnableMouseCapture)
+}
+
+/// Draw `app` once into an off-screen buffer of the given size, exactly as
+/// it would appear in a terminal, so what gets rendered can be checked
+/// without one.
+///
+/// ```
+/// use tui_frontend::{render, App, Item};
+///
+/// let mut app = App::builder()
+///     .tab("Fruit", vec![Item::new("apple", 1), Item::new("pear", 2)])
+///     .tab("Veg", vec![Item::new("leek", 3)])
+///     .build();
+/// app.list_mut().next();
+/// let buffer = render(&mut app, 40, 12);
+/// let row = |y| (0..40).map(|x| buffer.get(x, y).symbol.as_str()).collect::<String>();
+/// assert_eq!(row(2), " │ Fruit • Veg     ││apple            │ ");
+/// assert_eq!(buffer.get(3, 2).style().fg, Some(tui::style::Color::Cyan));
+/// assert!(row(5).starts_with(" │>> apple"));
+/// assert!(row(6).starts_with(" │   pear"));
+/// ```
+pub fn render(app: &mut App, width: u16, height: u16) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))
+        .expect("the test backend can't fail");
+    terminal.draw(|f| ui::ui(f, app)).expect("the test backend can't fail");
+    terminal.backend().buffer().clone()
+}
+
+/// Draw `app` once like [`render`] and return the text on screen, a line
+/// per row with trailing spaces trimmed, to share what the picker looks like.
+///
+/// ```
+/// use tui_frontend::{dump, App, Item};
+///
+/// let mut app = App::builder().tab("Fruit", vec![Item::new("apple", 1)]).build();
+/// let text = dump(&mut app, 40, 12);
+/// assert_eq!(text.lines().count(), 12);
+/// assert_eq!(text.lines().nth(2), Some(" │ Fruit           ││                 │"));
+/// ```
+pub fn dump(app: &mut App, width: u16, height: u16) -> String {
+    let buffer = render(app, width, height);
+    let mut text = String::new();
+    for y in 0..height {
+        let mut line = String::new();
+        let mut x = 0;
+        while x < width {
+            let symbol = &buffer.get(x, y).symbol;
+            line.push_str(symbol);
+            // A wide character covers the cells after it too
+            x += symbol.width().max(1) as u16;
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+// Highlight the first item, unless startup commands already selected one
+fn select_initial(app: &mut App) {
+    if app.config.initial_selection && app.list().state.selected().is_none() {
+        app.list_mut().next();
+    }
+}
+
+// Most missed ticks run in one go after the loop was held up
+const MAX_CATCH_UP_TICKS: usize = 3;
+
+/// Run `app` until the user quits, drawing to `terminal` and reading input
+/// from `events`. [`run_with`] calls this with the real terminal; with a
+/// [`TestBackend`] and a [`ScriptedSource`] the whole loop runs without one.
+///
+/// ```
+/// use crossterm::event::KeyCode;
+/// use tui::{backend::TestBackend, Terminal};
+/// use tui_frontend::{run_app, App, AppEvent, Item, ScriptedSource};
+///
+/// let mut app = App::builder()
+///     .items(vec![Item::new("apple", 1), Item::new("pear", 2), Item::new("plum", 3)])
+///     .build();
+/// let mut terminal = Terminal::new(TestBackend::new(40, 12)).unwrap();
+/// let mut events = ScriptedSource::new([
+///     AppEvent::key(KeyCode::Char('/')),
+///     AppEvent::key(KeyCode::Char('u')),
+///     AppEvent::key(KeyCode::Char('m')),
+///     AppEvent::key(KeyCode::Enter),
+///     AppEvent::key(KeyCode::Enter),
+/// ]);
+/// let selections = run_app(&mut terminal, &mut app, &mut events).unwrap();
+/// assert_eq!(selections[0].label, "plum");
+/// ```
+pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App, events: &mut impl EventSource) -> io::Result<Vec<Selection>> {
+    let tick_rate = app.tick_rate.max(Duration::from_millis(1));
+    let mut last_tick = Instant::now();
+    select_initial(app);
+    loop {
+        app.receive_streamed();
+        app.mark_selected_visited();
+        app.collapse_if_moved();
+        app.update_preview();
+        if app.clear_requested {
+            app.clear_requested = false;
+            terminal.clear()?;
+        }
+        terminal.draw(|f| ui::ui(f, app))?;
+        if app.reload_requested {
+            app.reload();
+            continue;
+        }
+
+        // Wake up for the next tick, but never wait longer than the poll
+        // timeout so input stays responsive with a slow tick rate
+        let timeout = tick_rate
+            .checked_sub(last_tick.elapsed())
+            .unwrap_or_else(|| Duration::from_secs(0))
+            .min(app.poll_timeout);
+
+        let flow = match events.next(timeout)? {
+            Some(AppEvent::Key(key)) => handler::handle_key(app, key),
+            Some(AppEvent::Mouse(mouse)) => {
+                handler::handle_mouse(app, mouse);
+                Flow::Continue
+            }
+            None => Flow::Continue,
+        };
+        app.pause_follow_if_moved();
+        match flow {
+            Flow::Continue => {}
+            Flow::Quit => return Ok(std::mem::take(&mut app.picked)),
+            // Anything picked earlier while staying open comes first
+            Flow::Select => {
+                let mut selections = std::mem::take(&mut app.picked);
+                selections.extend(app.selections());
+                return Ok(selections);
+            }
+        }
+
+        // Ticks stay on multiples of the tick rate from the start, so a slow
+        // frame doesn't push every later tick back. After a long stall only
+        // a few of the missed ticks run and the rest are skipped
+        let mut ticks = 0;
+        while last_tick.elapsed() >= tick_rate {
+            if ticks == MAX_CATCH_UP_TICKS {
+                let behind = last_tick.elapsed().as_nanos() / tick_rate.as_nanos();
+                last_tick += tick_rate * behind as u32;
+                break;
+            }
+            app.on_tick();
+            last_tick += tick_rate;
+            ticks += 1;
+        }
+    }
+}
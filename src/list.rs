@@ -0,0 +1,498 @@
+use std::{collections::HashSet, ops::RangeInclusive};
+use tui::widgets::ListState;
+
+use crate::model::Item;
+
+/// Whether a list entry can be selected. Entries that can't are skipped
+/// when moving through the list.
+pub trait Selectable {
+    fn selectable(&self) -> bool {
+        true
+    }
+}
+
+pub struct StatefulList<T> {
+    pub state: ListState,
+    pub items: Vec<T>,
+    // Indices into `items` in the order they are shown; the selection in
+    // `state` is a position within this view
+    pub view: Vec<usize>,
+    /// Indices into `items` of the entries marked for a multi-selection
+    pub checked: HashSet<usize>,
+    /// Index into `items` where a range selection started
+    pub anchor: Option<usize>,
+    /// Position in the view of the first entry drawn
+    pub offset: usize,
+}
+
+impl<T> StatefulList<T> {
+    pub fn with_items(items: Vec<T>) -> StatefulList<T> {
+        StatefulList {
+            state: ListState::default(),
+            view: (0..items.len()).collect(),
+            items,
+            checked: HashSet::new(),
+            anchor: None,
+            offset: 0,
+        }
+    }
+
+    /// Select the next entry that can be selected, wrapping around to the
+    /// first.
+    pub fn next(&mut self)
+    where
+        T: Selectable,
+    {
+        let len = self.view.len();
+        let start = self.state.selected().map_or(0, |i| i + 1);
+        let found = (0..len)
+            .map(|n| (start + n) % len)
+            .find(|&p| self.items[self.view[p]].selectable());
+        if found.is_some() {
+            self.state.select(found);
+        }
+    }
+
+    /// Select the previous entry that can be selected, wrapping around to
+    /// the last.
+    pub fn previous(&mut self)
+    where
+        T: Selectable,
+    {
+        let len = self.view.len();
+        let start = self.state.selected().unwrap_or(0) + len;
+        let found = (1..=len)
+            .map(|n| (start - n) % len)
+            .find(|&p| self.items[self.view[p]].selectable());
+        if found.is_some() {
+            self.state.select(found);
+        }
+    }
+
+    /// Select the next checked item in the view after the selection,
+    /// wrapping around. Does nothing if none are checked.
+    ///
+    /// ```
+    /// use tui_frontend::{Item, StatefulList};
+    ///
+    /// let mut list = StatefulList::with_items((0..8).map(|i| Item::new(format!("item {i}"), i)).collect());
+    /// list.checked.extend([1, 4, 6]);
+    /// // From nothing selected, the first checked item
+    /// list.next_checked();
+    /// assert_eq!(list.state.selected(), Some(1));
+    /// list.next_checked();
+    /// assert_eq!(list.state.selected(), Some(4));
+    /// list.next_checked();
+    /// assert_eq!(list.state.selected(), Some(6));
+    /// // Past the last back round to the first
+    /// list.next_checked();
+    /// assert_eq!(list.state.selected(), Some(1));
+    /// // And before the first round to the last
+    /// list.previous_checked();
+    /// assert_eq!(list.state.selected(), Some(6));
+    /// list.previous_checked();
+    /// assert_eq!(list.state.selected(), Some(4));
+    /// // From an unchecked item, the nearest checked one that way
+    /// list.select_index(5);
+    /// list.previous_checked();
+    /// assert_eq!(list.state.selected(), Some(4));
+    /// list.select_index(5);
+    /// list.next_checked();
+    /// assert_eq!(list.state.selected(), Some(6));
+    ///
+    /// // With nothing checked the selection stays put
+    /// list.clear_checked();
+    /// list.select_index(3);
+    /// list.next_checked();
+    /// assert_eq!(list.state.selected(), Some(3));
+    /// list.previous_checked();
+    /// assert_eq!(list.state.selected(), Some(3));
+    /// ```
+    pub fn next_checked(&mut self) {
+        let len = self.view.len();
+        let start = self.state.selected().map_or(0, |s| s + 1);
+        let found = (0..len)
+            .map(|n| (start + n) % len)
+            .find(|&p| self.checked.contains(&self.view[p]));
+        if found.is_some() {
+            self.state.select(found);
+        }
+    }
+
+    /// Select the previous checked item in the view before the selection,
+    /// wrapping around. Does nothing if none are checked.
+    pub fn previous_checked(&mut self) {
+        let len = self.view.len();
+        let start = self.state.selected().unwrap_or(0) + len;
+        let found = (1..=len)
+            .map(|n| (start - n) % len)
+            .find(|&p| self.checked.contains(&self.view[p]));
+        if found.is_some() {
+            self.state.select(found);
+        }
+    }
+
+    pub fn unselect(&mut self) {
+        self.state.select(None);
+    }
+
+    /// Select the entry at `position` in the view if it can be selected,
+    /// else the nearest one after it that can, else the nearest before it.
+    /// Selects nothing if no entry in the view can be selected.
+    ///
+    /// ```
+    /// use tui_frontend::{Item, StatefulList};
+    ///
+    /// let mut list = StatefulList::with_items(vec![Item::new("a", 1), Item::separator(), Item::new("b", 2), Item::separator()]);
+    /// list.select_nearest(1);
+    /// assert_eq!(list.state.selected(), Some(2));
+    /// list.select_nearest(3);
+    /// assert_eq!(list.state.selected(), Some(2));
+    /// list.select_nearest(9);
+    /// assert_eq!(list.state.selected(), Some(2));
+    ///
+    /// let mut separators = StatefulList::with_items(vec![Item::separator()]);
+    /// separators.select_nearest(0);
+    /// assert_eq!(separators.state.selected(), None);
+    /// ```
+    pub fn select_nearest(&mut self, position: usize)
+    where
+        T: Selectable,
+    {
+        let len = self.view.len();
+        let position = position.min(len);
+        let selectable = |p: &usize| self.items[self.view[*p]].selectable();
+        let found = (position..len).find(selectable).or_else(|| (0..position).rev().find(selectable));
+        self.state.select(found);
+    }
+
+    /// Index into `items` of the selected entry.
+    pub fn selected_index(&self) -> Option<usize> {
+        self.state.selected().and_then(|i| self.view.get(i).copied())
+    }
+
+    pub fn selected_item(&self) -> Option<&T> {
+        self.selected_index().map(|i| &self.items[i])
+    }
+
+    /// Items in the order they are currently shown.
+    pub fn visible(&self) -> impl Iterator<Item = &T> {
+        self.view.iter().map(move |&i| &self.items[i])
+    }
+
+    /// Select the entry at `index` in `items`, if it is shown.
+    pub fn select_index(&mut self, index: usize) {
+        if let Some(position) = self.view.iter().position(|&i| i == index) {
+            self.state.select(Some(position));
+        }
+    }
+
+    /// Insert a copy of the selected item right after it and select the copy.
+    /// Returns false if nothing is selected.
+    ///
+    /// ```
+    /// use tui_frontend::{Item, StatefulList};
+    ///
+    /// let labels = |list: &StatefulList<Item>| list.items.iter().map(|i| i.label.clone()).collect::<Vec<_>>();
+    /// let fresh = || {
+    ///     let mut list = StatefulList::with_items(vec![Item::new("a", 1), Item::new("b", 2), Item::new("c", 3)]);
+    ///     list.checked.extend([0, 2]);
+    ///     list
+    /// };
+    ///
+    /// // First: items after the copy move down, checks included
+    /// let mut list = fresh();
+    /// list.select_index(0);
+    /// assert!(list.duplicate_selected());
+    /// assert_eq!(labels(&list), ["a", "a", "b", "c"]);
+    /// assert_eq!(list.view, [0, 1, 2, 3]);
+    /// assert_eq!(list.checked, [0, 3].into());
+    /// assert_eq!(list.state.selected(), Some(1));
+    ///
+    /// // Middle
+    /// let mut list = fresh();
+    /// list.select_index(1);
+    /// assert!(list.duplicate_selected());
+    /// assert_eq!(labels(&list), ["a", "b", "b", "c"]);
+    /// assert_eq!(list.view, [0, 1, 2, 3]);
+    /// assert_eq!(list.checked, [0, 3].into());
+    /// assert_eq!(list.state.selected(), Some(2));
+    ///
+    /// // Last: the copy isn't checked even though the original is
+    /// let mut list = fresh();
+    /// list.select_index(2);
+    /// assert!(list.duplicate_selected());
+    /// assert_eq!(labels(&list), ["a", "b", "c", "c"]);
+    /// assert_eq!(list.view, [0, 1, 2, 3]);
+    /// assert_eq!(list.checked, [0, 2].into());
+    /// assert_eq!(list.state.selected(), Some(3));
+    ///
+    /// // Nothing selected
+    /// let mut list = fresh();
+    /// assert!(!list.duplicate_selected());
+    /// assert_eq!(labels(&list), ["a", "b", "c"]);
+    /// assert_eq!(list.checked, [0, 2].into());
+    /// assert_eq!(list.state.selected(), None);
+    /// ```
+    pub fn duplicate_selected(&mut self) -> bool
+    where
+        T: Clone,
+    {
+        match self.selected_item().cloned() {
+            Some(copy) => {
+                self.insert_after_selected(copy);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Insert `item` right after the selected one, or at the end when
+    /// nothing is selected, and select it.
+    pub fn insert_after_selected(&mut self, item: T) {
+        let (position, index) = match (self.state.selected(), self.selected_index()) {
+            (Some(position), Some(index)) => (position + 1, index + 1),
+            _ => (self.view.len(), self.items.len()),
+        };
+        self.items.insert(index, item);
+        for i in &mut self.view {
+            if *i >= index {
+                *i += 1;
+            }
+        }
+        self.checked = self.checked.iter().map(|&i| if i >= index { i + 1 } else { i }).collect();
+        self.view.insert(position, index);
+        self.state.select(Some(position));
+    }
+
+    /// Take the selected item out of the list, selecting the one after it.
+    pub fn remove_selected(&mut self) -> Option<T> {
+        let position = self.state.selected()?;
+        let index = self.selected_index()?;
+        let item = self.items.remove(index);
+        self.view.remove(position);
+        for i in &mut self.view {
+            if *i > index {
+                *i -= 1;
+            }
+        }
+        self.checked = self.checked.iter()
+            .filter(|&&i| i != index)
+            .map(|&i| if i > index { i - 1 } else { i })
+            .collect();
+        self.state.select(match self.view.len() {
+            0 => None,
+            len => Some(position.min(len - 1)),
+        });
+        Some(item)
+    }
+
+    /// Check the selected entry, or uncheck it if it is checked. Entries that
+    /// can't be selected are never checked.
+    pub fn toggle_checked(&mut self)
+    where
+        T: Selectable,
+    {
+        if let Some(index) = self.selected_index().filter(|&i| self.items[i].selectable()) {
+            if !self.checked.remove(&index) {
+                self.checked.insert(index);
+            }
+        }
+    }
+
+    /// Check every entry among `visible`, the indices into `items` being
+    /// shown, that can be selected. Those not in `visible` are left as they
+    /// are.
+    ///
+    /// ```
+    /// use tui_frontend::{App, Item};
+    ///
+    /// let items = vec![Item::new("apple", 1), Item::new("pear", 2), Item::separator(), Item::new("plum", 3), Item::new("fig", 4)];
+    /// let mut app = App::builder().items(items).build();
+    /// app.list_mut().checked.insert(4);
+    /// app.query = "p".to_string();
+    /// app.apply_filter();
+    /// let visible = app.list().view.clone();
+    /// assert_eq!(visible, [0, 1, 3]);
+    /// // The fig stays checked though the filter hides it
+    /// app.list_mut().check_all_visible(&visible);
+    /// assert_eq!(app.list().checked, [0, 1, 3, 4].into());
+    ///
+    /// app.list_mut().clear_checked();
+    /// app.query = "l".to_string();
+    /// app.apply_filter();
+    /// let visible = app.list().view.clone();
+    /// app.list_mut().check_all_visible(&visible);
+    /// assert_eq!(app.list().checked, [0, 3].into());
+    /// ```
+    pub fn check_all_visible(&mut self, visible: &[usize])
+    where
+        T: Selectable,
+    {
+        let items = &self.items;
+        self.checked.extend(visible.iter().copied().filter(|&i| items[i].selectable()));
+    }
+
+    /// Check the unchecked and uncheck the checked among `visible`, the
+    /// indices into `items` being shown. Entries that can't be selected and
+    /// those not in `visible` are left as they are.
+    ///
+    /// ```
+    /// use tui_frontend::{Item, StatefulList};
+    ///
+    /// let mut list = StatefulList::with_items((0..4).map(|i| Item::new(format!("item {i}"), i)).collect());
+    /// list.checked.extend([0, 3]);
+    /// // Item 3 is filtered out, so it stays checked
+    /// list.view = vec![0, 1, 2];
+    /// let visible = list.view.clone();
+    /// list.invert_checked(&visible);
+    /// assert_eq!(list.checked, [1, 2, 3].into());
+    /// ```
+    pub fn invert_checked(&mut self, visible: &[usize])
+    where
+        T: Selectable,
+    {
+        for &index in visible {
+            if self.items[index].selectable() && !self.checked.remove(&index) {
+                self.checked.insert(index);
+            }
+        }
+    }
+
+    pub fn clear_checked(&mut self) {
+        self.checked.clear();
+    }
+
+    /// View positions between the anchor and the selection, inclusive.
+    pub fn range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.anchor?;
+        let anchor = self.view.iter().position(|&i| i == anchor)?;
+        let selected = self.state.selected()?;
+        Some(anchor.min(selected)..=anchor.max(selected))
+    }
+
+    /// Check every entry in the anchored range that can be selected and drop
+    /// the anchor.
+    ///
+    /// ```
+    /// use tui_frontend::{Item, StatefulList};
+    ///
+    /// let mut list = StatefulList::with_items(vec![Item::new("a", 1), Item::separator(), Item::new("b", 2)]);
+    /// list.anchor = Some(0);
+    /// list.state.select(Some(2));
+    /// list.check_range();
+    /// assert_eq!(list.checked, [0, 2].into());
+    /// assert_eq!(list.anchor, None);
+    /// ```
+    pub fn check_range(&mut self)
+    where
+        T: Selectable,
+    {
+        if let Some(range) = self.range() {
+            let indices: Vec<usize> = range.map(|p| self.view[p]).filter(|&i| self.items[i].selectable()).collect();
+            self.checked.extend(indices);
+        }
+        self.anchor = None;
+    }
+
+    /// Move `offset` the least amount needed to keep the selection on
+    /// screen, given the height of a shown entry and of the window.
+    pub fn update_offset(&mut self, height: impl Fn(usize) -> usize, max_height: usize) {
+        self.offset = scroll_offset(self.offset, self.state.selected(), self.view.len(), height, max_height);
+    }
+
+    /// Replace the shown subset, keeping the selection on the same item when
+    /// it is still part of the view, and otherwise on the first entry that
+    /// can be selected. The scroll position is kept within the new view.
+    pub fn set_view(&mut self, view: Vec<usize>)
+    where
+        T: Selectable,
+    {
+        let selected = self.selected_index();
+        self.view = view;
+        self.offset = self.offset.min(self.view.len().saturating_sub(1));
+        match selected.map(|s| self.view.iter().position(|&i| i == s)) {
+            Some(Some(position)) => self.state.select(Some(position)),
+            Some(None) => self.select_nearest(0),
+            None => self.state.select(None),
+        }
+    }
+}
+
+impl StatefulList<Item> {
+    /// Select the item labelled `label` after the items changed under the
+    /// selection, or stay at the same position, moved back onto the list if
+    /// it is now past the end, when there is no such item.
+    pub fn reselect_by_label(&mut self, label: &str) {
+        let items = &self.items;
+        let position = match self.view.iter().position(|&i| items[i].label == label) {
+            Some(position) => Some(position),
+            None if self.view.is_empty() => None,
+            None => Some(self.state.selected().unwrap_or(0).min(self.view.len() - 1)),
+        };
+        self.state.select(position);
+    }
+}
+
+/// The offset closest to `offset` that keeps the selected entry on screen,
+/// given the number of shown entries, the height of each and of the window.
+/// This matches the scrolling done by tui's `List`, but only asks for the
+/// heights of entries near the window.
+pub fn scroll_offset(
+    offset: usize,
+    selected: Option<usize>,
+    len: usize,
+    height: impl Fn(usize) -> usize,
+    max_height: usize,
+) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let mut start = offset.min(len - 1);
+    let mut end = start;
+    let mut used = 0;
+    while end < len {
+        let h = height(end);
+        if used + h > max_height {
+            break;
+        }
+        used += h;
+        end += 1;
+    }
+    let selected = selected.unwrap_or(0).min(len - 1);
+    // A long jump only has to walk back one screen from the selection
+    if selected >= end && selected - end > max_height {
+        start = selected - max_height;
+        end = start;
+        used = 0;
+    }
+    while selected >= end {
+        used += height(end);
+        end += 1;
+        while used > max_height {
+            used = used.saturating_sub(height(start));
+            start += 1;
+        }
+    }
+    while selected < start {
+        start -= 1;
+        used += height(start);
+        while used > max_height {
+            end -= 1;
+            used = used.saturating_sub(height(end));
+        }
+    }
+    start
+}
+
+/// The offset keeping the selected entry on screen when entries run down one
+/// column of `rows` and on down a second, moving a column at a time.
+pub fn column_offset(offset: usize, selected: Option<usize>, rows: usize) -> usize {
+    let rows = rows.max(1);
+    let offset = offset / rows * rows;
+    match selected {
+        Some(selected) if selected < offset => selected / rows * rows,
+        Some(selected) if selected >= offset + 2 * rows => (selected / rows - 1) * rows,
+        _ => offset,
+    }
+}
@@ -0,0 +1,213 @@
+use tui::style::Style;
+
+use crate::{app::SortState, filter::PayloadFilter, list::{Selectable, StatefulList}, source::Source, stream::Stream, theme::Theme};
+
+/// A single entry in a list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Item {
+    pub label: String,
+    /// Arbitrary value carried along with the label and returned on selection
+    pub payload: usize,
+    /// Whether the selection has landed on this item
+    pub visited: bool,
+    /// Secondary value shown right-aligned at the end of the row
+    pub meta: Option<String>,
+    /// Dimmed line shown under the label, which is then drawn bold
+    pub subtitle: Option<String>,
+    /// Short text such as a count, drawn as a pill in its own style at the
+    /// far end of the row
+    pub badge: Option<(String, Style)>,
+    /// Laid over the row's style, such as to color the item
+    pub style: Option<Style>,
+    /// Drawn as a line across the list and never selected
+    pub separator: bool,
+    /// Items opened as a list of their own when this one is picked
+    pub children: Vec<Item>,
+    /// Glyph drawn in front of the label, but not part of it
+    pub icon: Option<String>,
+}
+
+impl Item {
+    pub fn new(label: impl Into<String>, payload: usize) -> Item {
+        Item { label: label.into(), payload, visited: false, meta: None, subtitle: None, badge: None, style: None, separator: false, children: Vec::new(), icon: None }
+    }
+
+    /// A line dividing groups of items, skipped over when moving.
+    pub fn separator() -> Item {
+        Item { separator: true, ..Item::new("", 0) }
+    }
+
+    pub fn with_meta(mut self, meta: impl Into<String>) -> Item {
+        self.meta = Some(meta.into());
+        self
+    }
+
+    pub fn with_subtitle(mut self, subtitle: impl Into<String>) -> Item {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    pub fn with_badge(mut self, badge: impl Into<String>, style: Style) -> Item {
+        self.badge = Some((badge.into(), style));
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Item {
+        self.style = Some(style);
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<Item>) -> Item {
+        self.children = children;
+        self
+    }
+
+    /// Draw `icon` in front of the label, leaving the label itself, and so
+    /// what's printed, searched and remembered, as it was.
+    ///
+    /// ```
+    /// use tui_frontend::{dump, App, Item};
+    ///
+    /// let mut app = App::builder().items(vec![Item::new("tui-frontend", 1).with_icon("#")]).build();
+    /// assert!(dump(&mut app, 40, 10).contains("# tui-frontend"));
+    /// assert_eq!(app.list().items[0].label, "tui-frontend");
+    /// app.query = "#".to_string();
+    /// app.apply_filter();
+    /// assert!(app.list().view.is_empty());
+    /// ```
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Item {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    /// Every field searching all fields looks through: the label, payload,
+    /// metadata, subtitle and badge, a line each so no match runs across
+    /// two of them.
+    ///
+    /// ```
+    /// use tui_frontend::{App, Config, Item};
+    ///
+    /// let apple = Item::new("apple", 42).with_meta("red").with_subtitle("a fruit");
+    /// assert_eq!(apple.search_text(), "apple\n42\nred\na fruit");
+    ///
+    /// let config = Config { search_all_fields: true, ..Config::default() };
+    /// let mut app = App::builder().config(config).items(vec![apple, Item::new("pear", 7).with_meta("green")]).build();
+    /// app.query = "red".to_string();
+    /// app.apply_filter();
+    /// assert_eq!(app.list().view, [0]);
+    /// app.query = "7".to_string();
+    /// app.apply_filter();
+    /// assert_eq!(app.list().view, [1]);
+    ///
+    /// app.config.search_all_fields = false;
+    /// app.apply_filter();
+    /// assert!(app.list().view.is_empty());
+    /// ```
+    pub fn search_text(&self) -> String {
+        let mut text = format!("{}\n{}", self.label, self.payload);
+        let fields = [self.meta.as_deref(), self.subtitle.as_deref(), self.badge.as_ref().map(|(badge, _)| badge.as_str())];
+        for field in fields.into_iter().flatten() {
+            text.push('\n');
+            text.push_str(field);
+        }
+        text
+    }
+}
+
+impl Selectable for Item {
+    fn selectable(&self) -> bool {
+        !self.separator
+    }
+}
+
+/// How a tab's items are narrowed down and ordered. The selection, scroll
+/// position and checked items are kept on the tab's list.
+///
+/// While a tab is active its state is on the [`App`](crate::App), where
+/// drawing and key handling use it, and it goes back to the tab when
+/// another one becomes active. Each tab so keeps its own view:
+///
+/// ```
+/// use tui_frontend::{App, Item, SortState};
+///
+/// let items = || vec![Item::new("apple", 1), Item::new("pear", 2), Item::new("plum", 3)];
+/// let mut app = App::builder().tab("one", items()).tab("two", items()).build();
+/// app.query = "l".to_string();
+/// app.sort_state = SortState::LabelDesc;
+/// app.apply_filter();
+/// app.list_mut().next();
+///
+/// app.next_tab();
+/// assert_eq!(app.query, "");
+/// assert_eq!(app.sort_state, SortState::Original);
+/// assert_eq!(app.list().view.len(), 3);
+///
+/// app.previous_tab();
+/// assert_eq!(app.query, "l");
+/// assert_eq!(app.sort_state, SortState::LabelDesc);
+/// assert_eq!(app.list().view.len(), 2);
+/// assert_eq!(app.list().selected_item().unwrap().label, "plum");
+/// ```
+#[derive(Default)]
+pub struct TabState {
+    pub query: String,
+    pub sort_state: SortState,
+    pub(crate) payload_filter: Option<PayloadFilter>,
+}
+
+/// A list opened from one of its items, kept while that item's children are
+/// shown in its place.
+pub struct Level {
+    /// Label of the item the children belong to
+    pub label: String,
+    pub items: StatefulList<Item>,
+    pub state: TabState,
+}
+
+/// A named list shown as one entry in the tabs bar.
+pub struct Tab {
+    pub title: String,
+    pub items: StatefulList<Item>,
+    /// Where the items were loaded from, if they can be reloaded
+    pub source: Option<Source>,
+    /// Number of items [`Tab::cap`] dropped when they were loaded
+    pub dropped: usize,
+    /// Used instead of the app's theme while this tab is active
+    pub theme: Option<Theme>,
+    /// Where more items come from while the app runs
+    pub stream: Option<Stream>,
+    /// The tab's view, while another tab is active
+    pub state: TabState,
+    /// Lists opened down to the one shown, outermost first
+    pub levels: Vec<Level>,
+}
+
+impl Tab {
+    pub fn new(title: impl Into<String>, items: Vec<Item>) -> Tab {
+        Tab { title: title.into(), items: StatefulList::with_items(items), source: None, dropped: 0, theme: None, stream: None, state: TabState::default(), levels: Vec::new() }
+    }
+
+    /// Whether the tab has no items that can be selected.
+    pub fn is_empty(&self) -> bool {
+        !self.items.items.iter().any(Selectable::selectable)
+    }
+
+    /// Keep at most `max` items, remembering how many were dropped.
+    pub fn cap(&mut self, max: Option<usize>) {
+        let list = &mut self.items;
+        self.dropped = 0;
+        let max = match max {
+            Some(max) if max < list.items.len() => max,
+            _ => return,
+        };
+        self.dropped = list.items.len() - max;
+        list.items.truncate(max);
+        list.view.retain(|&i| i < max);
+        list.checked.retain(|&i| i < max);
+        if list.state.selected().is_some_and(|s| s >= list.view.len()) {
+            list.state.select(list.view.len().checked_sub(1));
+        }
+    }
+}
+
+impl Selectable for Tab {}
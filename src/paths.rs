@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+use directories::ProjectDirs;
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "tui-frontend")
+}
+
+/// Where data persisted between runs lives, following each platform's
+/// conventions. `None` if the home directory can't be determined.
+pub(crate) fn state_dir() -> Option<PathBuf> {
+    let dirs = project_dirs()?;
+    // Only Linux has a dedicated state directory
+    Some(dirs.state_dir().unwrap_or_else(|| dirs.data_local_dir()).to_path_buf())
+}
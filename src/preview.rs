@@ -0,0 +1,190 @@
+use std::{
+    io::Read,
+    process::{Command, Stdio},
+    sync::{atomic::{AtomicBool, Ordering}, mpsc::{self, Receiver, TryRecvError}, Arc},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::activate::shell_quote;
+
+// How long the selection has to rest on an item before its preview runs
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+// How often the worker checks whether its command finished or was cancelled
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// Frames of the spinner shown while a preview runs
+const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+
+/// What the detail pane shows for the selected item's preview.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PreviewState {
+    /// Nothing selected
+    Empty,
+    /// Waiting for the selection to settle or the command to finish
+    Running,
+    /// The command's output
+    Done(String),
+    /// The command's error output, or why it couldn't be run
+    Failed(String),
+    /// The command was stopped before it finished
+    Cancelled,
+}
+
+/// Output of a shell command run against the selected item in the
+/// background, shown in the detail pane.
+pub struct Preview {
+    command: String,
+    /// Label the current state is for
+    label: Option<String>,
+    pub state: PreviewState,
+    /// When the selection last moved, until the command is started
+    changed_at: Option<Instant>,
+    // Result of the running command, tagged with the label it ran for
+    receiver: Option<Receiver<(String, PreviewState)>>,
+    // Set to have the running command killed
+    cancelled: Arc<AtomicBool>,
+    started_at: Instant,
+}
+
+impl Preview {
+    /// Every `{}` in `command` is replaced by the label, quoted for the shell.
+    pub fn new(command: impl Into<String>) -> Preview {
+        Preview {
+            command: command.into(),
+            label: None,
+            state: PreviewState::Empty,
+            changed_at: None,
+            receiver: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Called every frame with the selected label: starts the command once
+    /// the selection has settled and picks up its output when it's done.
+    pub fn update(&mut self, label: Option<&str>) {
+        if self.label.as_deref() != label {
+            self.label = label.map(str::to_string);
+            // A command still running for the old selection is stopped, and
+            // its output dropped along with the receiver
+            self.cancelled.store(true, Ordering::Relaxed);
+            self.receiver = None;
+            self.state = if label.is_some() { PreviewState::Running } else { PreviewState::Empty };
+            self.changed_at = label.map(|_| Instant::now());
+        }
+        if let (Some(changed_at), Some(label)) = (self.changed_at, &self.label) {
+            if changed_at.elapsed() >= DEBOUNCE {
+                self.changed_at = None;
+                self.start(label.clone());
+            }
+        }
+        if let Some(receiver) = &self.receiver {
+            match receiver.try_recv() {
+                Ok((label, state)) if Some(&label) == self.label.as_ref() => {
+                    self.state = state;
+                    self.receiver = None;
+                }
+                Ok(_) | Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => self.receiver = None,
+            }
+        }
+    }
+
+    fn start(&mut self, label: String) {
+        let line = self.command.replace("{}", &shell_quote(&label));
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.receiver = Some(receiver);
+        self.cancelled = Arc::clone(&cancelled);
+        self.started_at = Instant::now();
+        thread::spawn(move || {
+            let state = match run(&line, &cancelled) {
+                Some(state) => state,
+                None => return,
+            };
+            // The app may have moved on and dropped the receiver
+            let _ = sender.send((label, state));
+        });
+    }
+
+    /// Stop the running command, or the one about to start, and show that
+    /// it was cancelled. Returns false if there was none.
+    ///
+    /// ```
+    /// use tui_frontend::{Preview, PreviewState};
+    ///
+    /// let mut preview = Preview::new("sleep 10");
+    /// preview.update(Some("apple"));
+    /// assert!(preview.cancel());
+    /// assert_eq!(preview.state, PreviewState::Cancelled);
+    /// assert!(!preview.is_running());
+    /// assert!(!preview.cancel());
+    /// ```
+    pub fn cancel(&mut self) -> bool {
+        if self.receiver.is_none() && self.changed_at.is_none() {
+            return false;
+        }
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.receiver = None;
+        self.changed_at = None;
+        self.state = PreviewState::Cancelled;
+        true
+    }
+
+    /// Whether a command has been started and hasn't finished yet.
+    pub fn is_running(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Spinner frame for how long the command has been running.
+    pub fn spinner(&self) -> &'static str {
+        SPINNER[(self.started_at.elapsed().as_millis() / 100) as usize % SPINNER.len()]
+    }
+}
+
+// Run the command to the end, or kill it and give up as soon as `cancelled`
+// is set. Output is read on threads of its own so a full pipe can't stall
+// the command.
+fn run(line: &str, cancelled: &AtomicBool) -> Option<PreviewState> {
+    let spawned = Command::new("sh")
+        .arg("-c")
+        .arg(line)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(err) => return Some(PreviewState::Failed(format!("{}: {}", line, err))),
+    };
+    let read = |mut pipe: Box<dyn Read + Send>| thread::spawn(move || {
+        let mut output = Vec::new();
+        let _ = pipe.read_to_end(&mut output);
+        output
+    });
+    let stdout = read(Box::new(child.stdout.take().expect("stdout is piped")));
+    let stderr = read(Box::new(child.stderr.take().expect("stderr is piped")));
+    let status = loop {
+        if cancelled.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(err) => return Some(PreviewState::Failed(format!("{}: {}", line, err))),
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    Some(if status.success() {
+        PreviewState::Done(String::from_utf8_lossy(&stdout).into_owned())
+    } else if !stderr.is_empty() {
+        PreviewState::Failed(String::from_utf8_lossy(&stderr).into_owned())
+    } else {
+        PreviewState::Failed(format!("{}: {}", line, status))
+    })
+}
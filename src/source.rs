@@ -0,0 +1,66 @@
+use std::{borrow::Cow, fs, io, path::PathBuf, process::Command};
+
+use crate::model::Item;
+
+/// Where a tab's items come from, so they can be loaded again on demand.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// One item per line of the file
+    File(PathBuf),
+    /// One item per line of the output of a `sh -c` command
+    Command(String),
+}
+
+impl Source {
+    /// Read the items, numbering payloads from 1 in line order.
+    pub fn load(&self) -> io::Result<Vec<Item>> {
+        self.load_lossy().map(|(items, _)| items)
+    }
+
+    /// Read the items like [`Source::load`], along with how many lines
+    /// weren't valid UTF-8. Invalid sequences in those are replaced with
+    /// `�`.
+    pub fn load_lossy(&self) -> io::Result<(Vec<Item>, usize)> {
+        let bytes = match self {
+            Source::File(path) => fs::read(path)?,
+            Source::Command(command) => {
+                let output = Command::new("sh").arg("-c").arg(command).output()?;
+                if !output.status.success() {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    let message = stderr.lines().next().unwrap_or("").to_string();
+                    return Err(io::Error::other(format!("{}: {}", output.status, message)));
+                }
+                output.stdout
+            }
+        };
+        let mut invalid = 0;
+        let items = lines(&bytes)
+            .enumerate()
+            .map(|(i, line)| {
+                let label = String::from_utf8_lossy(line);
+                if let Cow::Owned(_) = label {
+                    invalid += 1;
+                }
+                Item::new(label, i + 1)
+            })
+            .collect();
+        Ok((items, invalid))
+    }
+
+    /// Title for a tab showing this source.
+    pub fn title(&self) -> String {
+        match self {
+            Source::File(path) => path.display().to_string(),
+            Source::Command(command) => command.clone(),
+        }
+    }
+}
+
+// Split like str::lines, on "\n" or "\r\n" with no empty line after a final
+// newline, but without needing valid UTF-8
+fn lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let bytes = if bytes.is_empty() { None } else { Some(bytes.strip_suffix(b"\n").unwrap_or(bytes)) };
+    bytes.into_iter()
+        .flat_map(|bytes| bytes.split(|&b| b == b'\n'))
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+}
@@ -0,0 +1,199 @@
+//! Tabs and items described as data, for driving the picker from a JSON
+//! file rather than one item per line. YAML files are refused rather than
+//! listed line by line.
+//!
+//! A spec holds a list of tabs, each with a title and its items:
+//!
+//! ```json
+//! {
+//!   "tabs": [
+//!     {
+//!       "title": "Projects",
+//!       "items": [
+//!         { "label": "tui-frontend", "icon": "", "badge": "3", "style": { "fg": "cyan", "bold": true },
+//!           "children": [{ "label": "src" }, { "label": "README.md", "payload": 42 }] },
+//!         { "separator": true },
+//!         { "label": "archive", "subtitle": "old work", "meta": "2019" }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Unknown fields are refused so typos don't go unnoticed. Children are
+//! opened as a list of their own by picking their parent, and an icon is
+//! drawn in front of the label without becoming part of what's picked.
+//! Payloads default to the item's place in its
+//! list, counting from 1 like lines of a file.
+
+use std::{fs, io, path::Path};
+use serde::{de::Error as _, Deserialize, Deserializer};
+use tui::style::{Color, Modifier, Style};
+
+use crate::model::Item;
+
+/// Every tab the picker shows.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Spec {
+    pub tabs: Vec<TabSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TabSpec {
+    pub title: String,
+    #[serde(default)]
+    pub items: Vec<ItemSpec>,
+}
+
+/// An item and the items nested under it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ItemSpec {
+    pub label: String,
+    pub payload: Option<usize>,
+    pub icon: Option<String>,
+    pub meta: Option<String>,
+    pub subtitle: Option<String>,
+    pub badge: Option<String>,
+    /// Style of the badge, reversed text when not given
+    pub badge_style: Option<StyleSpec>,
+    pub style: Option<StyleSpec>,
+    pub separator: bool,
+    pub children: Vec<ItemSpec>,
+}
+
+/// A style given by color names and modifier flags.
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StyleSpec {
+    #[serde(deserialize_with = "color")]
+    pub fg: Option<Color>,
+    #[serde(deserialize_with = "color")]
+    pub bg: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Spec {
+    /// Read a spec from a JSON file.
+    pub fn load(path: &Path) -> io::Result<Spec> {
+        Spec::parse(&fs::read_to_string(path)?)
+            .map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))
+    }
+
+    /// Read a spec from JSON, refusing one that doesn't describe any tabs
+    /// or has fields it doesn't know.
+    ///
+    /// ```
+    /// use tui_frontend::Spec;
+    ///
+    /// let spec = Spec::parse(r#"{ "tabs": [{ "title": "Fruit", "items": [{ "label": "apple" }] }] }"#).unwrap();
+    /// assert_eq!(spec.tabs[0].items[0].label, "apple");
+    ///
+    /// let err = Spec::parse(r#"{ "tabs": [{ "title": "Fruit", "items": [{ "lable": "apple" }] }] }"#).unwrap_err();
+    /// assert!(err.to_string().contains("unknown field `lable`"));
+    /// let err = Spec::parse(r#"{ "tabs": [{ "title": "Fruit", "items": [{ "label": "apple", "style": { "fg": "teal" } }] }] }"#).unwrap_err();
+    /// assert!(err.to_string().contains("unknown color teal"));
+    /// ```
+    pub fn parse(json: &str) -> io::Result<Spec> {
+        let spec: Spec = serde_json::from_str(json).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if spec.tabs.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no tabs given"));
+        }
+        Ok(spec)
+    }
+}
+
+impl TabSpec {
+    /// The tab's items, each carrying its children.
+    pub fn items(&self) -> Vec<Item> {
+        items(&self.items)
+    }
+}
+
+impl ItemSpec {
+    fn item(&self, position: usize) -> Item {
+        if self.separator {
+            return Item::separator();
+        }
+        let mut item = Item::new(self.label.as_str(), self.payload.unwrap_or(position + 1));
+        item.icon = self.icon.clone();
+        item.meta = self.meta.clone();
+        item.subtitle = self.subtitle.clone();
+        if let Some(badge) = &self.badge {
+            let style = self.badge_style.map_or(Style::default().add_modifier(Modifier::REVERSED), StyleSpec::style);
+            item = item.with_badge(badge.as_str(), style);
+        }
+        item.style = self.style.map(StyleSpec::style);
+        item.children = items(&self.children);
+        item
+    }
+}
+
+impl StyleSpec {
+    pub fn style(self) -> Style {
+        let mut style = Style::default();
+        if let Some(fg) = self.fg {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = self.bg {
+            style = style.bg(bg);
+        }
+        let flags = [
+            (self.bold, Modifier::BOLD),
+            (self.dim, Modifier::DIM),
+            (self.italic, Modifier::ITALIC),
+            (self.underline, Modifier::UNDERLINED),
+        ];
+        for (set, modifier) in flags {
+            if set {
+                style = style.add_modifier(modifier);
+            }
+        }
+        style
+    }
+}
+
+fn items(specs: &[ItemSpec]) -> Vec<Item> {
+    specs.iter().enumerate().map(|(position, spec)| spec.item(position)).collect()
+}
+
+// A color by name, as a 0-255 palette index or as #rrggbb
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Color>, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    let color = match name.to_lowercase().as_str() {
+        "reset" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" => Color::Gray,
+        "darkgray" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        hex if hex.len() == 7 && hex.is_ascii() && hex.starts_with('#') => {
+            let channel = |n: usize| u8::from_str_radix(&hex[n..n + 2], 16);
+            match (channel(1), channel(3), channel(5)) {
+                (Ok(r), Ok(g), Ok(b)) => Color::Rgb(r, g, b),
+                _ => return Err(D::Error::custom(format!("unknown color {}", name))),
+            }
+        }
+        index => match index.parse() {
+            Ok(index) => Color::Indexed(index),
+            Err(_) => return Err(D::Error::custom(format!("unknown color {}", name))),
+        },
+    };
+    Ok(Some(color))
+}
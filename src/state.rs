@@ -0,0 +1,66 @@
+use std::{fs, io, path::PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+// Most recently used labels kept in the state file
+const MRU_LIMIT: usize = 50;
+
+/// Data persisted between runs.
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct State {
+    /// Labels of previously selected items, most recent first
+    #[serde(default)]
+    pub mru: Vec<String>,
+    /// Labels of items that have been visited
+    #[serde(default)]
+    pub visited: Vec<String>,
+    /// Search queries entered, oldest first
+    #[serde(default)]
+    pub searches: Vec<String>,
+    /// Command lines entered, oldest first
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// Labels of pinned items, in the order they were pinned
+    #[serde(default)]
+    pub pins: Vec<String>,
+    /// Name of the theme last chosen
+    #[serde(default)]
+    pub theme: Option<String>,
+}
+
+impl State {
+    fn path() -> Option<PathBuf> {
+        paths::state_dir().map(|dir| dir.join("state.json"))
+    }
+
+    /// Load the saved state, falling back to an empty one if there is none
+    /// or it can't be read.
+    pub fn load() -> State {
+        let path = State::path();
+        if path.is_none() {
+            eprintln!("warning: no home directory found, nothing will be remembered between runs");
+        }
+        path
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = match State::path() {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn record(&mut self, label: &str) {
+        self.mru.retain(|l| l != label);
+        self.mru.insert(0, label.to_string());
+        self.mru.truncate(MRU_LIMIT);
+    }
+}
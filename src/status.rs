@@ -0,0 +1,71 @@
+//! Segments making up the status bar.
+//!
+//! Each segment is drawn from the app every frame, on the left after the
+//! ones before it, centered, or against the right edge. The mode is the
+//! only segment shown by default; the bar's messages, prompts and
+//! questions go between the left and right segments.
+//!
+//! ```
+//! use tui::text::Span;
+//! use tui_frontend::{dump, status::{self, Place}, App, Item};
+//!
+//! let mut app = App::builder()
+//!     .items(vec![Item::new("apple", 1), Item::new("pear", 2)])
+//!     .status_segment(Place::Right, status::position())
+//!     .status_segment(Place::Center, Box::new(|app| Span::raw(format!("{} picked", app.picked.len()))))
+//!     .build();
+//! app.list_mut().next();
+//! let screen = dump(&mut app, 40, 10);
+//! let bar = screen.lines().find(|line| line.contains("NORMAL")).unwrap();
+//! assert!(bar.contains("0 picked"));
+//! assert!(bar.ends_with("1/2"));
+//! ```
+
+use std::time::{SystemTime, UNIX_EPOCH};
+use tui::text::Span;
+
+use crate::app::{App, Mode};
+
+/// Draws a segment of the status bar.
+pub type Segment = Box<dyn Fn(&App) -> Span<'static>>;
+
+/// Where on the status bar a segment is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Place {
+    Left,
+    Center,
+    Right,
+}
+
+/// The mode the app is in, in the theme's color for it.
+pub fn mode() -> Segment {
+    Box::new(|app| {
+        let theme = app.active_theme();
+        let (mode, style) = match app.mode {
+            Mode::Normal => (" NORMAL ", theme.mode_normal),
+            Mode::Search => (" SEARCH ", theme.mode_search),
+            Mode::Command => (" COMMAND ", theme.mode_command),
+            Mode::Visual => (" VISUAL ", theme.mode_visual),
+            Mode::Confirm => (" CONFIRM ", theme.mode_confirm),
+            Mode::Hint => (" HINT ", theme.mode_hint),
+        };
+        Span::styled(mode, style)
+    })
+}
+
+/// The selection's place among the items shown, such as `3/10`.
+pub fn position() -> Segment {
+    Box::new(|app| match app.list().state.selected() {
+        Some(position) => Span::raw(format!("{}/{}", position + 1, app.list().view.len())),
+        None => Span::raw(""),
+    })
+}
+
+/// The time of day in UTC as hours and minutes, since there's no time zone
+/// database to go by.
+pub fn clock() -> Segment {
+    Box::new(|_| {
+        let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs());
+        Span::raw(format!("{:02}:{:02}", seconds / 3600 % 24, seconds / 60 % 60))
+    })
+}
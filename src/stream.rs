@@ -0,0 +1,96 @@
+//! Lines arriving while the app runs, appended to a tab as they come in.
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+/// Lines read by a worker thread and picked up by the app every frame.
+pub struct Stream {
+    receiver: Receiver<String>,
+}
+
+impl Stream {
+    /// Lines sent over `receiver`, to feed a tab from your own thread.
+    ///
+    /// ```
+    /// use std::sync::mpsc;
+    /// use tui::{backend::TestBackend, Terminal};
+    /// use tui_frontend::{run_app, App, Config, ScriptedSource, Stream};
+    ///
+    /// let (sender, receiver) = mpsc::channel();
+    /// let mut app = App::builder()
+    ///     .config(Config { follow: true, ..Config::default() })
+    ///     .stream_tab("log", Stream::new(receiver))
+    ///     .build();
+    /// for line in ["one", "two", "three"] {
+    ///     sender.send(line.to_string()).unwrap();
+    /// }
+    /// let mut terminal = Terminal::new(TestBackend::new(40, 10)).unwrap();
+    /// // The newest line is selected until the selection moves up
+    /// run_app(&mut terminal, &mut app, &mut ScriptedSource::keys("kq")).unwrap();
+    /// assert_eq!(app.list().selected_item().unwrap().label, "two");
+    /// assert!(!app.following);
+    ///
+    /// // Lines beyond `max_items` are dropped
+    /// app.config.max_items = Some(4);
+    /// for line in ["four", "five", "six"] {
+    ///     sender.send(line.to_string()).unwrap();
+    /// }
+    /// run_app(&mut terminal, &mut app, &mut ScriptedSource::keys("q")).unwrap();
+    /// assert_eq!(app.list().items.len(), 4);
+    /// assert_eq!(app.active_tab().dropped, 2);
+    /// ```
+    pub fn new(receiver: Receiver<String>) -> Stream {
+        Stream { receiver }
+    }
+
+    /// Lines the `sh -c` command prints, as it prints them.
+    pub fn command(command: &str) -> io::Result<Stream> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stream = Stream::read(BufReader::new(stdout));
+        // Reap the command once it's done so it doesn't linger
+        thread::spawn(move || child.wait());
+        Ok(stream)
+    }
+
+    pub fn file(path: &Path) -> io::Result<Stream> {
+        Ok(Stream::read(BufReader::new(File::open(path)?)))
+    }
+
+    /// Lines piped into the program. Keys are still read from the terminal.
+    pub fn stdin() -> Stream {
+        Stream::read(BufReader::new(io::stdin()))
+    }
+
+    fn read(reader: impl BufRead + Send + 'static) -> Stream {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.split(b'\n') {
+                let Ok(line) = line else { break };
+                let line = line.strip_suffix(b"\r").unwrap_or(&line);
+                // Stop reading once the app is gone
+                if sender.send(String::from_utf8_lossy(line).into_owned()).is_err() {
+                    break;
+                }
+            }
+        });
+        Stream { receiver }
+    }
+
+    /// Lines that arrived since the last call.
+    pub fn drain(&self) -> Vec<String> {
+        self.receiver.try_iter().collect()
+    }
+}
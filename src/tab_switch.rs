@@ -0,0 +1,44 @@
+//! Callbacks run when another tab becomes active.
+
+use std::collections::HashSet;
+
+use crate::{list::StatefulList, model::{Item, Tab}};
+
+/// Called with the index of the newly active tab and the tab itself, which
+/// it may change, e.g. to fill in its items.
+pub type OnTabSwitch = Box<dyn FnMut(usize, &mut Tab)>;
+
+/// Fill each tab with the items `load` returns for it the first time it
+/// becomes active, rather than loading every tab up front.
+///
+/// ```
+/// use tui_frontend::{tab_switch::lazy_load, App, Config, Item};
+///
+/// let mut app = App::builder()
+///     .config(Config { max_items: Some(3), ..Config::default() })
+///     .tab("one", Vec::new())
+///     .tab("two", Vec::new())
+///     .on_tab_switch(lazy_load(|tab| (1..=5).map(|i| Item::new(format!("{} {i}", tab.title), i)).collect()))
+///     .build();
+/// // The first tab is loaded as the app starts, the second once it's
+/// // switched to, and neither keeps more than `max_items`
+/// assert_eq!(app.list().items.len(), 3);
+/// assert_eq!(app.list().selected_item().unwrap().label, "one 1");
+/// app.select_tab(1);
+/// assert_eq!(app.list().items.len(), 3);
+/// assert_eq!(app.active_tab().dropped, 2);
+/// assert_eq!(app.list().selected_item().unwrap().label, "two 1");
+/// // Switching back leaves the first tab as it was
+/// app.select_tab(0);
+/// assert_eq!(app.active_tab().dropped, 2);
+/// ```
+pub fn lazy_load(mut load: impl FnMut(&Tab) -> Vec<Item> + 'static) -> OnTabSwitch {
+    let mut loaded = HashSet::new();
+    Box::new(move |index, tab| {
+        if loaded.insert(index) {
+            let items = load(tab);
+            tab.dropped = 0;
+            tab.items = StatefulList::with_items(items);
+        }
+    })
+}
@@ -0,0 +1,297 @@
+use tui::{style::{Color, Modifier, Style}, symbols::DOT};
+
+/// How many colors the terminal can show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorLevel {
+    /// The basic eight, as on the Linux console
+    Eight,
+    /// The basic eight and their bright versions
+    Sixteen,
+    /// The xterm 256-color palette
+    Indexed,
+    /// Any RGB color
+    TrueColor,
+}
+
+impl ColorLevel {
+    /// Guess from the environment: `COLORTERM` announces RGB support, and
+    /// otherwise crossterm goes by whether `TERM` mentions 256 colors.
+    pub fn detect() -> ColorLevel {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorLevel::TrueColor;
+        }
+        match crossterm::style::available_color_count() {
+            256.. => ColorLevel::Indexed,
+            16..=255 => ColorLevel::Sixteen,
+            _ => ColorLevel::Eight,
+        }
+    }
+
+    /// The closest color to `color` the terminal can show.
+    ///
+    /// ```
+    /// use tui::style::Color;
+    /// use tui_frontend::ColorLevel;
+    ///
+    /// assert_eq!(ColorLevel::Eight.color(Color::LightCyan), Color::Cyan);
+    /// assert_eq!(ColorLevel::Eight.color(Color::Rgb(250, 10, 10)), Color::Red);
+    /// assert_eq!(ColorLevel::Sixteen.color(Color::Indexed(236)), Color::Black);
+    /// assert_eq!(ColorLevel::Indexed.color(Color::Rgb(0, 0, 255)), Color::Indexed(21));
+    /// assert_eq!(ColorLevel::TrueColor.color(Color::Rgb(1, 2, 3)), Color::Rgb(1, 2, 3));
+    /// ```
+    pub fn color(self, color: Color) -> Color {
+        let basic = |count: usize| {
+            let rgb = rgb(color);
+            ANSI[..count].iter()
+                .min_by_key(|(_, ansi)| distance(rgb, *ansi))
+                .map_or(color, |&(named, _)| named)
+        };
+        match (self, color) {
+            (_, Color::Reset) | (ColorLevel::TrueColor, _) => color,
+            (ColorLevel::Indexed, Color::Rgb(r, g, b)) => Color::Indexed(nearest_indexed((r, g, b))),
+            (ColorLevel::Indexed, _) => color,
+            (ColorLevel::Sixteen, Color::Rgb(..) | Color::Indexed(_)) => basic(16),
+            (ColorLevel::Sixteen, _) => color,
+            (ColorLevel::Eight, _) => basic(8),
+        }
+    }
+}
+
+impl std::str::FromStr for ColorLevel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<ColorLevel, ()> {
+        match s {
+            "8" => Ok(ColorLevel::Eight),
+            "16" => Ok(ColorLevel::Sixteen),
+            "256" => Ok(ColorLevel::Indexed),
+            "truecolor" | "24bit" => Ok(ColorLevel::TrueColor),
+            _ => Err(()),
+        }
+    }
+}
+
+// The sixteen named colors as xterm draws them, the basic eight first
+const ANSI: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+// Levels of each channel in the 6x6x6 color cube of the 256-color palette
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(i @ 0..=15) => ANSI[i as usize].1,
+        Color::Indexed(i @ 16..=231) => {
+            let i = i - 16;
+            (CUBE[(i / 36) as usize], CUBE[(i / 6 % 6) as usize], CUBE[(i % 6) as usize])
+        }
+        Color::Indexed(i) => {
+            let level = 8 + (i - 232) * 10;
+            (level, level, level)
+        }
+        named => ANSI.iter().find(|(c, _)| *c == named).map_or((0, 0, 0), |&(_, rgb)| rgb),
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    d(r1, r2) + d(g1, g2) + d(b1, b2)
+}
+
+// Index of the cube or gray ramp entry closest to an RGB color
+fn nearest_indexed(rgb: (u8, u8, u8)) -> u8 {
+    (16..=255u8).min_by_key(|&i| distance(rgb, self::rgb(Color::Indexed(i)))).unwrap_or(16)
+}
+
+/// Styles used when drawing the app.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub banner: Style,
+    /// Borders around the tabs, list and details
+    pub border: Style,
+    /// Border of the focused pane
+    pub focused_border: Style,
+    pub tabs: Style,
+    pub tab_highlight: Style,
+    /// Style of the selected list row
+    pub highlight: Style,
+    /// Fill the selected row out to the edge of the list with the
+    /// highlight, rather than only the text on it
+    pub full_width_highlight: bool,
+    /// Style of the parts of a label matching the search query
+    pub matched: Style,
+    pub error: Style,
+    pub line_number: Style,
+    /// Items not visited yet, when tracking visits
+    pub unread: Style,
+    pub read: Style,
+    /// Lines dividing groups of items
+    pub separator: Style,
+    /// Right-aligned item metadata
+    pub meta: Style,
+    /// Labels of items with a subtitle, and the subtitles under them
+    pub title: Style,
+    pub subtitle: Style,
+    /// Marker shown next to checked items
+    pub checked: Style,
+    /// Marker shown next to pinned items
+    pub pinned: Style,
+    /// Letters labelling rows in hint mode
+    pub hint: Style,
+    /// Rows inside a range being selected
+    pub visual: Style,
+    /// Background of every other row, while striping is on
+    pub stripe_bg: Option<Color>,
+    /// Footer with item counts and payload figures
+    pub stats: Style,
+    /// Mode indicators at the start of the status bar
+    pub mode_normal: Style,
+    pub mode_search: Style,
+    pub mode_command: Style,
+    pub mode_visual: Style,
+    pub mode_confirm: Style,
+    pub mode_hint: Style,
+    /// Drawn between tab titles; may be empty
+    pub divider: String,
+}
+
+impl Theme {
+    /// A copy of the theme using only colors the terminal can show.
+    pub fn degrade(&self, level: ColorLevel) -> Theme {
+        let color = |color| level.color(color);
+        let style = |style: Style| Style { fg: style.fg.map(color), bg: style.bg.map(color), ..style };
+        Theme {
+            banner: style(self.banner),
+            border: style(self.border),
+            focused_border: style(self.focused_border),
+            tabs: style(self.tabs),
+            tab_highlight: style(self.tab_highlight),
+            highlight: style(self.highlight),
+            full_width_highlight: self.full_width_highlight,
+            matched: style(self.matched),
+            error: style(self.error),
+            line_number: style(self.line_number),
+            unread: style(self.unread),
+            read: style(self.read),
+            separator: style(self.separator),
+            meta: style(self.meta),
+            title: style(self.title),
+            subtitle: style(self.subtitle),
+            checked: style(self.checked),
+            pinned: style(self.pinned),
+            hint: style(self.hint),
+            visual: style(self.visual),
+            stats: style(self.stats),
+            mode_normal: style(self.mode_normal),
+            mode_search: style(self.mode_search),
+            mode_command: style(self.mode_command),
+            mode_visual: style(self.mode_visual),
+            mode_confirm: style(self.mode_confirm),
+            mode_hint: style(self.mode_hint),
+            stripe_bg: self.stripe_bg.map(color),
+            divider: self.divider.clone(),
+        }
+    }
+
+    /// Dark text and strong highlights for terminals with a light
+    /// background.
+    pub fn light() -> Theme {
+        Theme {
+            focused_border: Style::default().fg(Color::Blue),
+            tabs: Style::default().fg(Color::Black),
+            tab_highlight: Style::default().fg(Color::Blue),
+            highlight: Style::default()
+                .bg(Color::Blue)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            matched: Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            pinned: Style::default().fg(Color::Magenta),
+            visual: Style::default().bg(Color::Gray),
+            stripe_bg: Some(Color::Indexed(254)),
+            ..Theme::default()
+        }
+    }
+
+    /// No colors, only bold, dim, underlined and reversed text.
+    pub fn monochrome() -> Theme {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let reversed = Style::default().add_modifier(Modifier::REVERSED);
+        Theme {
+            focused_border: bold,
+            tabs: Style::default(),
+            tab_highlight: reversed,
+            highlight: reversed.add_modifier(Modifier::BOLD),
+            matched: bold.add_modifier(Modifier::UNDERLINED),
+            error: bold,
+            checked: bold,
+            pinned: bold,
+            visual: reversed,
+            stripe_bg: None,
+            mode_normal: reversed,
+            mode_search: reversed,
+            mode_command: reversed,
+            mode_visual: reversed,
+            mode_confirm: reversed.add_modifier(Modifier::BOLD),
+            mode_hint: reversed,
+            hint: reversed.add_modifier(Modifier::BOLD),
+            ..Theme::default()
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            banner: Style::default().add_modifier(Modifier::BOLD),
+            separator: Style::default().add_modifier(Modifier::DIM),
+            meta: Style::default().add_modifier(Modifier::DIM),
+            border: Style::default(),
+            focused_border: Style::default().fg(Color::Cyan),
+            tabs: Style::default().fg(Color::White),
+            tab_highlight: Style::default().fg(Color::Cyan),
+            highlight: Style::default()
+                .bg(Color::Cyan)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+            full_width_highlight: true,
+            matched: Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            error: Style::default().fg(Color::Red),
+            line_number: Style::default().add_modifier(Modifier::DIM),
+            unread: Style::default().add_modifier(Modifier::BOLD),
+            read: Style::default().add_modifier(Modifier::DIM),
+            title: Style::default().add_modifier(Modifier::BOLD),
+            subtitle: Style::default().add_modifier(Modifier::DIM),
+            checked: Style::default().fg(Color::Green),
+            pinned: Style::default().fg(Color::Yellow),
+            hint: Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD),
+            visual: Style::default().bg(Color::DarkGray),
+            stripe_bg: Some(Color::Indexed(236)),
+            stats: Style::default().add_modifier(Modifier::DIM),
+            mode_normal: Style::default().bg(Color::Blue).fg(Color::Black).add_modifier(Modifier::BOLD),
+            mode_search: Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD),
+            mode_command: Style::default().bg(Color::Green).fg(Color::Black).add_modifier(Modifier::BOLD),
+            mode_visual: Style::default().bg(Color::Magenta).fg(Color::Black).add_modifier(Modifier::BOLD),
+            mode_confirm: Style::default().bg(Color::Red).fg(Color::Black).add_modifier(Modifier::BOLD),
+            mode_hint: Style::default().bg(Color::Yellow).fg(Color::Black).add_modifier(Modifier::BOLD),
+            divider: DOT.to_string(),
+        }
+    }
+}
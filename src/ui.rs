@@ -0,0 +1,741 @@
+use tui::{backend::Backend,
+        widgets::{List, Block, Borders, Clear, ListItem, ListState, Paragraph, Tabs},
+        layout::{Alignment, Layout, Constraint, Direction, Rect},
+        style::{Color, Modifier, Style},
+        text::{Span, Spans},
+        Frame};
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{app::{App, Mode, Pane, SortState}, config::{Overflow, TabPosition}, filter, list::{column_offset, scroll_offset}, model::Item, preview::PreviewState, status::Place, theme::Theme};
+
+// Mirrors the layout done by Tabs::render: each title is padded by one
+// column on either side and followed by the divider
+fn tab_bounds(area: Rect, titles: &[Spans], divider: &str) -> Vec<(u16, u16)> {
+    let divider_width = Span::raw(divider).width() as u16;
+    let mut bounds = Vec::new();
+    let mut x = area.left();
+    for title in titles {
+        x = x.saturating_add(1);
+        if x >= area.right() {
+            break;
+        }
+        let end = x.saturating_add(title.width() as u16).min(area.right());
+        bounds.push((x, end));
+        x = end.saturating_add(1).saturating_add(divider_width);
+    }
+    bounds
+}
+
+fn highlight_matches<'a>(label: &'a str, query: &str, ignore_case: bool, style: Style) -> Spans<'a> {
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for range in filter::highlight_ranges(label, query, ignore_case) {
+        if range.start > last {
+            spans.push(Span::raw(&label[last..range.start]));
+        }
+        spans.push(Span::styled(&label[range.clone()], style));
+        last = range.end;
+    }
+    if last < label.len() {
+        spans.push(Span::raw(&label[last..]));
+    }
+    Spans::from(spans)
+}
+
+pub(crate) fn ui<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    let theme = app.active_theme().clone();
+    if app.config.bar {
+        return render_bar(f, app, &theme);
+    }
+    if app.zen {
+        return render_zen(f, app, &theme);
+    }
+
+    let mut root_constraints = vec![Constraint::Min(0), Constraint::Length(1)];
+    if app.config.banner.is_some() {
+        root_constraints.insert(0, Constraint::Length(1));
+    }
+    if app.config.stats {
+        root_constraints.insert(root_constraints.len() - 1, Constraint::Length(1));
+    }
+    // However large the margin, leave at least half the screen for content
+    let size = f.size();
+    let margin = app.config.margin.min(size.width / 4).min(size.height / 4);
+    let mut root = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(margin)
+        .constraints(root_constraints).split(size);
+    if let Some(banner) = &app.config.banner {
+        let banner = Paragraph::new(Span::styled(banner.as_str(), theme.banner)).alignment(Alignment::Center);
+        f.render_widget(banner, root.remove(0));
+    }
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+                     Constraint::Percentage(app.split),
+                     Constraint::Percentage(100 - app.split)
+        ].as_ref()).split(root[0]);
+    app.panes_area = root[0];
+    app.divider_x = chunks[1].x;
+    let left_constraints = if app.config.show_tabs {
+        // The title and tabs, plus borders above and below unless they are
+        // dropped
+        let height = if app.config.focus_borders_only { 2 } else { 3 };
+        match app.config.tab_position {
+            TabPosition::Top => vec![Constraint::Length(height), Constraint::Min(0)],
+            TabPosition::Bottom => vec![Constraint::Min(0), Constraint::Length(height)],
+        }
+    } else {
+        vec![Constraint::Percentage(100)]
+    };
+    let mut left_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(0)
+        .constraints(left_constraints).split(chunks[0]);
+    if app.config.tab_position == TabPosition::Bottom {
+        left_chunks.reverse();
+    }
+    let list_chunk = *left_chunks.last().unwrap();
+    app.list_area = list_chunk;
+
+    if app.config.show_tabs {
+        let titles: Vec<Spans> = app.tabs.items.iter().map(|t| { Spans::from(t.title.clone()) }).collect();
+        let block = Block::default().title("Tabs").borders(borders(app, Pane::Tabs)).border_style(theme.border);
+        app.tabs_area = block.inner(left_chunks[0]);
+        app.tab_bounds = tab_bounds(app.tabs_area, &titles, &theme.divider);
+        let titles = Tabs::new(titles)
+            .block(block)
+            .style(theme.tabs)
+            .highlight_style(theme.tab_highlight)
+            .select(app.tabs.state.selected().unwrap_or_default())
+            .divider(theme.divider.as_str());
+        f.render_widget(titles, left_chunks[0]);
+    }
+
+    render_list(f, app, &theme, list_chunk);
+
+    render_detail(f, app, &theme, chunks[1]);
+
+    if app.config.stats {
+        render_stats(f, app, &theme, root[1]);
+    }
+    render_status_bar(f, app, &theme, *root.last().unwrap());
+
+    if let Some(pending) = &app.pending_confirm {
+        render_confirm(f, &pending.message, &theme);
+    }
+}
+
+// Just the list's rows across the whole screen, with the status bar back only
+// while a prompt or question needs it
+fn render_zen<B: Backend>(f: &mut Frame<B>, app: &mut App, theme: &Theme) {
+    let prompt = matches!(app.mode, Mode::Command | Mode::Search | Mode::Confirm);
+    let constraints = if prompt {
+        vec![Constraint::Min(0), Constraint::Length(1)]
+    } else {
+        vec![Constraint::Percentage(100)]
+    };
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints).split(f.size());
+    // Nothing else is drawn, so nothing else can be clicked
+    app.tabs_area = Rect::default();
+    app.tab_bounds.clear();
+    app.detail_area = Rect::default();
+    app.panes_area = Rect::default();
+    render_list(f, app, theme, chunks[0]);
+    if prompt {
+        render_status_bar(f, app, theme, chunks[1]);
+    }
+    if let Some(pending) = &app.pending_confirm {
+        render_confirm(f, &pending.message, theme);
+    }
+}
+
+// A box in the middle of the screen asking a yes or no question
+fn render_confirm<B: Backend>(f: &mut Frame<B>, message: &str, theme: &Theme) {
+    let size = f.size();
+    let width = (Span::raw(message).width() as u16 + 4).max(20).min(size.width);
+    // A message too long for the screen, such as a command line, is wrapped
+    // rather than cut off
+    let mut text = wrap_spans(vec![Span::raw(message)], width.saturating_sub(2) as usize);
+    text.push(Spans::from("y / n"));
+    let height = (text.len() as u16 + 2).min(size.height);
+    let area = Rect {
+        x: size.x + (size.width - width) / 2,
+        y: size.y + (size.height - height) / 2,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .title("Confirm")
+        .borders(Borders::ALL)
+        .border_style(theme.focused_border);
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(text).block(block).alignment(Alignment::Center), area);
+}
+
+const HIGHLIGHT_SYMBOL: &str = ">> ";
+// Marks the selection at the end of a right-to-left row
+pub(crate) const RTL_HIGHLIGHT_SYMBOL: &str = " <<";
+
+// Columns the hint letters and the space after them take in hint mode
+const HINT_WIDTH: usize = 3;
+
+// Narrowest each column of a two-column list may be
+const MIN_COLUMN_WIDTH: u16 = 20;
+
+// Separates items in bar mode
+const BAR_GAP: &str = "  ";
+
+// The items on one line with the status bar below, scrolled horizontally so
+// the selected item is always in view
+fn render_bar<B: Backend>(f: &mut Frame<B>, app: &mut App, theme: &Theme) {
+    let size = f.size();
+    let bar = Rect { height: size.height.min(1), ..size };
+    let status = Rect { y: size.y + 1, height: size.height.saturating_sub(1).min(1), ..size };
+    app.list_area = bar;
+    app.row_bounds.clear();
+
+    let gap = BAR_GAP.len();
+    let widths: Vec<usize> = app.list().visible().map(|i| icon_width(i) + i.label.width() + gap).collect();
+    let list = app.list_mut();
+    if let Some(selected) = list.state.selected() {
+        list.offset = list.offset.min(selected);
+        while list.offset < selected && widths[list.offset..=selected].iter().sum::<usize>() > bar.width as usize {
+            list.offset += 1;
+        }
+    }
+
+    let list = app.list();
+    let mut spans = Vec::new();
+    for (position, item) in list.visible().enumerate().skip(list.offset) {
+        let style = if list.state.selected() == Some(position) {
+            theme.highlight
+        } else {
+            Style::default()
+        };
+        if let Some(icon) = &item.icon {
+            spans.push(Span::styled(format!("{} ", icon), style));
+        }
+        spans.push(Span::styled(item.label.as_str(), style));
+        spans.push(Span::raw(BAR_GAP));
+    }
+    f.render_widget(Paragraph::new(Spans::from(spans)), bar);
+    if status.height > 0 {
+        render_status_bar(f, app, theme, status);
+    }
+}
+
+// Longest query quoted in full in the list title
+const TITLE_QUERY_WIDTH: usize = 20;
+
+// "List (340)", or how many items the filter leaves, e.g.
+// `List (12/340 matching "foo")`, plus how many were dropped on loading
+fn list_title(app: &App) -> String {
+    let tab = app.active_tab();
+    let loaded = tab.items.items.len();
+    // Separators aren't items, and a filtered view has none
+    let separators = tab.items.items.iter().filter(|i| i.separator).count();
+    let mut counts = if app.query.is_empty() && app.payload_filter.is_none() {
+        (loaded - separators).to_string()
+    } else {
+        format!("{}/{}", tab.items.view.len(), loaded - separators)
+    };
+    if !app.query.is_empty() {
+        let mut query: String = app.query.chars().take(TITLE_QUERY_WIDTH).collect();
+        if query.len() < app.query.len() {
+            query.push('…');
+        }
+        counts.push_str(&format!(" matching \"{}\"", query));
+    }
+    if tab.dropped > 0 {
+        counts.push_str(&format!(", showing {} of {}", loaded, loaded + tab.dropped));
+    }
+    // The items opened down to the shown list, as a breadcrumb
+    let path: String = tab.levels.iter().map(|level| format!(" › {}", level.label)).collect();
+    format!("List{} ({})", path, counts)
+}
+
+fn render_list<B: Backend>(f: &mut Frame<B>, app: &mut App, theme: &Theme, area: Rect) {
+    let mut block = Block::default().borders(borders(app, Pane::List)).border_style(border_style(app, theme, Pane::List));
+    if !app.zen {
+        block = block.title(list_title(app));
+    }
+    let inner = block.inner(area);
+
+    // An empty view gets a line saying why rather than a blank pane
+    if app.list().view.is_empty() {
+        let message = if app.active_tab().is_empty() { "No items" } else { "No matches" };
+        let text = Paragraph::new(Span::styled(message, theme.meta))
+            .block(block)
+            .style(content_style(app, Pane::List))
+            .alignment(Alignment::Center);
+        f.render_widget(text, area);
+        app.row_bounds.clear();
+        app.second_column = None;
+        app.selected_row.clear();
+        return;
+    }
+
+    let list = app.list();
+    let range = if app.mode == Mode::Visual { list.range() } else { None };
+    let show_checks = !list.checked.is_empty() || range.is_some();
+    let show_pins = !app.pinned.is_empty();
+    let ignore_case = app.config.case.ignores_case(&app.query);
+    let gutter_width = list.view.len().to_string().len();
+    // Two columns are only used when there's room for both
+    let two_columns = app.config.two_columns && inner.width >= 2 * MIN_COLUMN_WIDTH;
+    let column_width = if two_columns { inner.width / 2 } else { inner.width };
+    let label_width = (column_width as usize).saturating_sub(HIGHLIGHT_SYMBOL.len());
+    let hinting = app.mode == Mode::Hint;
+    let prefix_width = if hinting { HINT_WIDTH } else { 0 }
+        + if app.config.line_numbers { gutter_width + 1 } else { 0 }
+        + if show_checks { 2 } else { 0 }
+        + if show_pins { 2 } else { 0 };
+    let text_width = label_width.saturating_sub(prefix_width);
+    app.list_text_width = text_width;
+    app.list_height = inner.height as usize;
+    app.list_columns = if two_columns { 2 } else { 1 };
+    let list = app.list();
+
+    // Work out the scroll position ourselves, from the height each item
+    // will have, so that only the items on screen need to be built and rows
+    // can be mapped back to items for mouse clicks
+    let height = |position: usize| app.row_height(position);
+    let rows = (inner.height as usize).max(1);
+    let mut row_bounds = Vec::new();
+    let offset = if two_columns {
+        // Items run down the first column and on down the second, a line
+        // each
+        let offset = column_offset(list.offset, list.state.selected(), rows);
+        for (n, position) in (offset..list.view.len()).take(2 * rows).enumerate() {
+            let top = inner.top() + (n % rows) as u16;
+            row_bounds.push((top, top + 1, position));
+        }
+        offset
+    } else {
+        let offset = scroll_offset(list.offset, list.state.selected(), list.view.len(), height, inner.height as usize);
+        let mut top = inner.top();
+        for position in offset..list.view.len() {
+            if top >= inner.bottom() {
+                break;
+            }
+            let bottom = top.saturating_add(height(position) as u16);
+            row_bounds.push((top, bottom, position));
+            top = bottom;
+        }
+        offset
+    };
+
+    let hints = if hinting { app.hint_targets(&row_bounds) } else { Vec::new() };
+    let selected = list.state.selected();
+    let mut selected_row = Vec::new();
+    let mut items: Vec<ListItem> = row_bounds
+        .iter()
+        .map(|&(_, _, position)| {
+            let index = list.view[position];
+            let i = &list.items[index];
+            if i.separator {
+                let line = Span::styled("─".repeat(label_width), theme.separator);
+                if app.config.rtl {
+                    return ListItem::new(Spans::from(vec![Span::raw(" ".repeat(HIGHLIGHT_SYMBOL.len())), line]));
+                }
+                return ListItem::new(line);
+            }
+            let rtl = app.is_rtl(i);
+            let mut prefix = Vec::new();
+            if hinting {
+                let hint = hints.iter().find(|&&(_, p)| p == position).map_or("", |(hint, _)| hint.as_str());
+                prefix.push(Span::styled(hint.to_string(), theme.hint));
+                prefix.push(Span::raw(" ".repeat(HINT_WIDTH - hint.len())));
+            }
+            if app.config.line_numbers {
+                let number = format!("{:>width$} ", position + 1, width = gutter_width);
+                prefix.push(Span::styled(number, theme.line_number));
+            }
+            if show_checks {
+                let mark = if list.checked.contains(&index) { "✓ " } else { "  " };
+                prefix.push(Span::styled(mark, theme.checked));
+            }
+            if show_pins {
+                let mark = if app.pinned.contains(&i.label) { "* " } else { "  " };
+                prefix.push(Span::styled(mark, theme.pinned));
+            }
+            if let Some(icon) = &i.icon {
+                prefix.push(Span::raw(format!("{} ", icon)));
+            }
+            let lead_width = prefix_width + icon_width(i);
+            let mut label = highlight_matches(&i.label, &app.query, ignore_case, theme.matched);
+            if i.subtitle.is_some() {
+                for span in &mut label.0 {
+                    span.style = theme.title.patch(span.style);
+                }
+            }
+            // Long labels are cut short or wrapped so the metadata and badge
+            // keep their place
+            let mut lines = wrap_spans(label.0, app.label_room(i));
+            if app.config.overflow == Overflow::Truncate {
+                lines.truncate(1);
+            }
+            let mut end = Vec::new();
+            if let Some(meta) = app.meta(i) {
+                end.push(Span::styled(meta, theme.meta));
+            }
+            if let Some((badge, style)) = &i.badge {
+                if !end.is_empty() {
+                    end.push(Span::raw(" "));
+                }
+                end.push(Span::styled(format!(" {} ", badge), *style));
+            }
+            let end_width: usize = end.iter().map(Span::width).sum();
+            if rtl {
+                // Each line is pushed against the marker at the far end,
+                // and the metadata and badge go first instead
+                let room = text_width.saturating_sub(RTL_HIGHLIGHT_SYMBOL.len() + icon_width(i));
+                for (n, line) in lines.iter_mut().enumerate() {
+                    let lead = if n == 0 { std::mem::take(&mut end) } else { Vec::new() };
+                    let pad = room.saturating_sub(line.width() + if n == 0 { end_width } else { 0 });
+                    line.0.splice(0..0, lead.into_iter().chain([Span::raw(" ".repeat(pad))]));
+                }
+            } else if !end.is_empty() {
+                let pad = text_width.saturating_sub(icon_width(i) + lines[0].width() + end_width);
+                lines[0].0.push(Span::raw(" ".repeat(pad)));
+                lines[0].0.extend(end);
+            }
+            // Continuation lines are indented to line up with the label
+            for (n, line) in lines.iter_mut().enumerate() {
+                let lead = if n == 0 { prefix.clone() } else { vec![Span::raw(" ".repeat(lead_width))] };
+                line.0.splice(0..0, lead);
+            }
+            if let Some(subtitle) = &i.subtitle {
+                lines.push(Spans::from(vec![
+                    Span::raw(" ".repeat(lead_width)),
+                    Span::styled(subtitle.as_str(), theme.subtitle),
+                ]));
+            }
+            let indent = " ".repeat(prefix_width + 2);
+            lines.extend(app.expansion(index).into_iter().map(|l| Spans::from(Span::styled(indent.clone() + &l, theme.meta))));
+            lines.extend((0..app.config.row_spacing).map(|_| Spans::default()));
+            lines.truncate(height(position));
+            // Without a full width highlight, only the text on the row
+            // takes it
+            if selected == Some(position) && !theme.full_width_highlight {
+                for span in lines.iter_mut().flat_map(|line| line.0.iter_mut()) {
+                    span.style = span.style.patch(theme.highlight);
+                }
+            }
+            if selected == Some(position) {
+                selected_row = lines.iter()
+                    .map(|line| line.0.iter().map(|span| span.content.as_ref()).collect::<String>().trim_end().to_string())
+                    .collect();
+                while selected_row.last().is_some_and(String::is_empty) {
+                    selected_row.pop();
+                }
+            }
+            // Marking the selection on either side means drawing the marker
+            // here rather than leaving it to the list
+            if app.config.rtl {
+                let blank = " ".repeat(HIGHLIGHT_SYMBOL.len());
+                for (n, line) in lines.iter_mut().enumerate() {
+                    let marker = if n == 0 && !rtl && selected == Some(position) { HIGHLIGHT_SYMBOL } else { blank.as_str() };
+                    line.0.insert(0, Span::raw(marker.to_string()));
+                }
+                if rtl && selected == Some(position) {
+                    if let Some(line) = lines.first_mut() {
+                        line.0.push(Span::raw(RTL_HIGHLIGHT_SYMBOL));
+                    }
+                }
+            }
+            let mut style = match (app.config.track_visited, i.visited) {
+                (false, _) => Style::default(),
+                (true, false) => theme.unread,
+                (true, true) => theme.read,
+            };
+            if app.config.color_by_payload {
+                let color = gradient(i.payload, app.payload_range);
+                style = style.fg(app.config.colors.map_or(color, |level| level.color(color)));
+            }
+            if let Some(item_style) = i.style {
+                style = style.patch(item_style);
+            }
+            // Striped by position in the view, so filtering keeps rows
+            // alternating
+            if let (true, Some(bg), 1) = (app.config.stripes, theme.stripe_bg, position % 2) {
+                style = style.bg(bg);
+            }
+            if range.as_ref().is_some_and(|r| r.contains(&position)) {
+                style = style.patch(theme.visual);
+            }
+            ListItem::new(lines).style(style)
+        })
+    .collect();
+
+    // Checked items scrolled out of view, above and below it. Finding them
+    // means walking the whole view, so it's only done once something is
+    // checked
+    let checked = |positions: &[usize]| positions.iter().filter(|i| list.checked.contains(i)).count();
+    let (above, below) = match (row_bounds.first(), row_bounds.last()) {
+        _ if list.checked.is_empty() => (0, 0),
+        (Some(&(_, _, first)), Some(&(_, _, last))) => (checked(&list.view[..first]), checked(&list.view[last + 1..])),
+        _ => (0, 0),
+    };
+
+    let style = content_style(app, Pane::List);
+    let highlight = if theme.full_width_highlight { theme.highlight } else { Style::default() };
+    let symbol = if app.config.rtl { "" } else { HIGHLIGHT_SYMBOL };
+    let widget = |items| List::new(items).style(style).highlight_style(highlight).highlight_symbol(symbol);
+    if two_columns {
+        // Each column is its own list, holding the selection if it falls in
+        // that column's items
+        let second = items.split_off(rows.min(items.len()));
+        let left = Rect { width: column_width, ..inner };
+        let right = Rect { x: inner.x + column_width, width: inner.width - column_width, ..inner };
+        f.render_widget(block.style(style), area);
+        for (items, column, first) in [(items, left, offset), (second, right, offset + rows)] {
+            let mut state = ListState::default();
+            state.select(selected.filter(|&s| s >= first && s < first + rows).map(|s| s - first));
+            f.render_stateful_widget(widget(items), column, &mut state);
+        }
+        app.second_column = Some((right.x, offset + rows));
+    } else {
+        // Only rows from the offset on are handed to the widget, so the
+        // selection is relative to it
+        let mut state = ListState::default();
+        state.select(selected.map(|s| s - offset));
+        f.render_stateful_widget(widget(items).block(block), area, &mut state);
+        app.second_column = None;
+    }
+    // Their counts go on the top and bottom edges, by the right corner
+    for (count, arrow, y) in [(above, "▲", area.top()), (below, "▼", area.bottom().saturating_sub(1))] {
+        let text = format!("{}{}", arrow, count);
+        let width = text.width() as u16;
+        if count > 0 && area.height > 0 && width + 2 <= area.width {
+            f.render_widget(Paragraph::new(Span::styled(text, theme.checked)), Rect { x: area.right() - width - 1, y, width, height: 1 });
+        }
+    }
+
+    app.list_mut().offset = offset;
+    app.row_bounds = row_bounds;
+    app.selected_row = selected_row;
+}
+
+// Number of lines wrap_spans splits `text` into
+pub(crate) fn wrapped_line_count(text: &str, width: usize) -> usize {
+    let width = width.max(1);
+    let mut lines = 1;
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > width && used > 0 {
+            lines += 1;
+            used = 0;
+        }
+        used += w;
+    }
+    lines
+}
+
+// Columns an item's icon takes in front of its label, with the space after it
+pub(crate) fn icon_width(item: &Item) -> usize {
+    item.icon.as_ref().map_or(0, |icon| icon.width() + 1)
+}
+
+// Split spans into lines at most `width` columns wide, breaking between
+// characters and keeping each piece's style
+fn wrap_spans(spans: Vec<Span>, width: usize) -> Vec<Spans> {
+    let width = width.max(1);
+    let mut lines = vec![Vec::new()];
+    let mut used = 0;
+    for span in spans {
+        let mut piece = String::new();
+        for c in span.content.chars() {
+            let w = c.width().unwrap_or(0);
+            if used + w > width && used > 0 {
+                if !piece.is_empty() {
+                    lines.last_mut().unwrap().push(Span::styled(std::mem::take(&mut piece), span.style));
+                }
+                lines.push(Vec::new());
+                used = 0;
+            }
+            piece.push(c);
+            used += w;
+        }
+        if !piece.is_empty() {
+            lines.last_mut().unwrap().push(Span::styled(piece, span.style));
+        }
+    }
+    lines.into_iter().map(Spans::from).collect()
+}
+
+// Green for the smallest payload through to red for the largest
+fn gradient(payload: usize, (min, max): (usize, usize)) -> Color {
+    let t = if max > min {
+        (payload.saturating_sub(min)) as f64 / (max - min) as f64
+    } else {
+        0.0
+    };
+    Color::Rgb((255.0 * t) as u8, (255.0 * (1.0 - t)) as u8, 0)
+}
+
+// Without borders a pane keeps its title row but its content gets the
+// columns and rows the borders took
+fn borders(app: &App, pane: Pane) -> Borders {
+    if app.zen || (app.config.focus_borders_only && app.focus != pane) {
+        Borders::NONE
+    } else {
+        Borders::ALL
+    }
+}
+
+fn border_style(app: &App, theme: &Theme, pane: Pane) -> Style {
+    if app.focus == pane { theme.focused_border } else { theme.border }
+}
+
+// Dims everything drawn in a pane without focus, on top of the spans' own
+// styles
+fn content_style(app: &App, pane: Pane) -> Style {
+    if app.config.dim_unfocused && app.focus != pane {
+        Style::default().add_modifier(Modifier::DIM)
+    } else {
+        Style::default()
+    }
+}
+
+fn render_detail<B: Backend>(f: &mut Frame<B>, app: &mut App, theme: &Theme, area: Rect) {
+    let title = match &app.preview {
+        Some(preview) if preview.state == PreviewState::Running => format!("Preview {}", preview.spinner()),
+        Some(_) => "Preview".to_string(),
+        None => "Details".to_string(),
+    };
+    let block = Block::default()
+        .title(title)
+        .borders(borders(app, Pane::Detail))
+        .border_style(border_style(app, theme, Pane::Detail));
+    app.detail_area = block.inner(area);
+    let lines = match (&app.preview, app.list().selected_item()) {
+        (Some(preview), _) => match &preview.state {
+            PreviewState::Done(output) => output.lines().map(Spans::from).collect(),
+            PreviewState::Failed(error) => error.lines().map(|l| Spans::from(Span::styled(l, theme.error))).collect(),
+            PreviewState::Cancelled => vec![Spans::from(Span::styled("cancelled", theme.meta))],
+            PreviewState::Empty | PreviewState::Running => Vec::new(),
+        },
+        (None, Some(item)) => {
+            let mut lines = vec![Spans::from(item.label.as_str())];
+            if let Some(subtitle) = &item.subtitle {
+                lines.push(Spans::from(Span::styled(subtitle.as_str(), theme.subtitle)));
+            }
+            lines.extend([
+                Spans::default(),
+                Spans::from(format!("payload: {}", item.payload)),
+                Spans::from(format!("tab: {}", app.active_tab().title)),
+            ]);
+            lines
+        }
+        (None, None) => Vec::new(),
+    };
+    let detail = Paragraph::new(lines)
+        .block(block)
+        .style(content_style(app, Pane::Detail))
+        .scroll((app.detail_scroll, 0));
+    f.render_widget(detail, area);
+}
+
+fn render_stats<B: Backend>(f: &mut Frame<B>, app: &App, theme: &Theme, area: Rect) {
+    let stats = app.stats();
+    let mut text = format!(
+        "{} items, {} filtered out, {} checked",
+        stats.total,
+        stats.total - stats.shown,
+        app.list().checked.len(),
+    );
+    if stats.shown > 0 {
+        text.push_str(&format!(
+            " │ payload sum {} min {} max {} avg {:.2}",
+            stats.sum, stats.min, stats.max, stats.average(),
+        ));
+    }
+    f.render_widget(Paragraph::new(Span::styled(text, theme.stats)), area);
+}
+
+// The segments drawn in `place`, a space apart
+fn status_segments(app: &App, place: Place) -> Spans<'static> {
+    let mut spans = Vec::new();
+    for (_, segment) in app.status_segments.iter().filter(|(p, _)| *p == place) {
+        let span = segment(app);
+        if span.content.is_empty() {
+            continue;
+        }
+        if !spans.is_empty() {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(span);
+    }
+    Spans::from(spans)
+}
+
+fn render_status_bar<B: Backend>(f: &mut Frame<B>, app: &App, theme: &Theme, area: Rect) {
+    let left = status_segments(app, Place::Left);
+    let width = if left.0.is_empty() { 0 } else { left.width() as u16 + 1 };
+    let parts = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(width), Constraint::Min(0)].as_ref())
+        .split(area);
+    f.render_widget(Paragraph::new(left), parts[0]);
+    let area = parts[1];
+
+    let prompt = match app.mode {
+        Mode::Command => Some(":"),
+        Mode::Search => Some("/"),
+        Mode::Normal | Mode::Visual | Mode::Confirm | Mode::Hint => None,
+    };
+    if let Some(prompt) = prompt {
+        let parts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+            .split(area);
+        f.render_widget(Paragraph::new(prompt), parts[0]);
+        f.render_widget(app.input.widget(), parts[1]);
+        // Ambiguous completions are listed at the far end of the prompt
+        if let Some(completion) = &app.completion {
+            let text = completion.candidates.join(" ");
+            let width = (text.width() as u16).min(parts[1].width / 2);
+            let list = Rect { x: parts[1].right() - width, width, ..parts[1] };
+            f.render_widget(Paragraph::new(Span::styled(text, theme.meta)).alignment(Alignment::Right), list);
+        }
+        return;
+    }
+
+    if let Some(pending) = &app.pending_confirm {
+        f.render_widget(Paragraph::new(format!("{} (y/n)", pending.message)), area);
+        return;
+    }
+
+    if let (Some(_), Some(leader)) = (app.pending_leader, app.keymap.leader) {
+        let text = format!("{} …", leader);
+        f.render_widget(Paragraph::new(text), area);
+        return;
+    }
+
+    let line = match &app.status {
+        Some(status) if status.error => Span::styled(status.text.as_str(), theme.error),
+        Some(status) => Span::raw(status.text.as_str()),
+        None if app.recording.is_some() => {
+            let register = app.recording.as_ref().map(|(register, _)| *register).unwrap_or_default();
+            Span::raw(format!("recording @{}", register))
+        }
+        None if app.following && app.active_tab().stream.is_some() => Span::raw("following"),
+        None if !app.list().checked.is_empty() => Span::raw(format!("{} checked", app.list().checked.len())),
+        None if app.sort_state != SortState::Original => Span::raw(app.sort_state.describe()),
+        None => Span::raw(""),
+    };
+    // The message gives way to the right segments, and the centered ones
+    // are drawn over it
+    let right = status_segments(app, Place::Right);
+    let width = (right.width() as u16).min(area.width);
+    let message = Rect { width: area.width - width, ..area };
+    f.render_widget(Paragraph::new(Spans::from(line)), message);
+    f.render_widget(Paragraph::new(status_segments(app, Place::Center)).alignment(Alignment::Center), area);
+    f.render_widget(Paragraph::new(right).alignment(Alignment::Right), area);
+}